@@ -24,9 +24,16 @@ fn test_source_schema_prompts() {
     dbg!(result);
 }
 
+fn test_hosts_schema_prompts() {
+    let schema: Schema = serde_yaml::from_str(include_str!("./prompts/hosts.yaml")).unwrap();
+    let result = schema.prompt(false).expect("Prompt");
+    dbg!(result);
+}
+
 fn main() {
     test_source_schema_prompts();
     test_basic_schema_prompts();
     test_array_schema_prompts();
     test_nested_schema_prompts();
+    test_hosts_schema_prompts();
 }
@@ -0,0 +1,112 @@
+#[cfg(any(feature = "items-command", feature = "validate-command"))]
+use std::process::{Command, Stdio};
+
+#[cfg(any(feature = "items-command", feature = "validate-command"))]
+/// Builds a command which runs the given line in the platform's shell.
+fn shell(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
+
+#[cfg(feature = "items-command")]
+/// Runs the command, returning each non-blank line of its output as an item.
+pub(crate) fn run_items_command(command: &str) -> Result<Vec<serde_json::Value>, String> {
+    let output = shell(command)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run items command {command:?}: {e}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Items command {command:?} failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let items = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(serde_json::Value::from)
+        .collect::<Vec<_>>();
+
+    if items.is_empty() {
+        return Err(format!("Items command {command:?} produced no items"));
+    }
+
+    Ok(items)
+}
+
+#[cfg(not(feature = "items-command"))]
+pub(crate) fn run_items_command(command: &str) -> Result<Vec<serde_json::Value>, String> {
+    Err(format!(
+        "Items command {command:?} cannot be run without the `items-command` feature"
+    ))
+}
+
+#[cfg(feature = "validate-command")]
+/// Runs the command with the value on stdin.
+///
+/// The outer error is returned if the command could not be run, the
+/// inner error if the command rejected the value.
+pub(crate) fn run_validate_command(
+    command: &str,
+    value: &serde_json::Value,
+) -> Result<Result<(), String>, String> {
+    use std::io::Write;
+
+    let input = match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    };
+
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run validate command {command:?}: {e}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The command may exit without reading its input.
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run validate command {command:?}: {e}"))?;
+
+    if output.status.success() {
+        return Ok(Ok(()));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if stderr.is_empty() {
+        Ok(Err(format!(
+            "Value {value} was rejected ({})",
+            output.status
+        )))
+    } else {
+        Ok(Err(stderr))
+    }
+}
+
+#[cfg(not(feature = "validate-command"))]
+pub(crate) fn run_validate_command(
+    command: &str,
+    _value: &serde_json::Value,
+) -> Result<Result<(), String>, String> {
+    Err(format!(
+        "Validate command {command:?} cannot be run without the `validate-command` feature"
+    ))
+}
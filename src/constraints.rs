@@ -1,11 +1,16 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display};
+
 use dialoguer::Validator;
 use indexmap::IndexMap;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 
-use crate::value::TraitIntBounds;
+use crate::command::run_items_command;
+use crate::value::{ByteSize, TraitIntBounds};
 use crate::Field;
 
-#[derive(serde::Deserialize, Clone, Copy)]
+#[derive(serde::Deserialize, Clone, Copy, PartialEq)]
 /// The constraints for collection types (array, set, hashmap, etc...)
 pub struct CollectionConstraints {
     #[serde(default)]
@@ -14,6 +19,103 @@ pub struct CollectionConstraints {
     #[serde(default = "<usize as TraitIntBounds>::max")]
     /// The maximum number of the items.
     pub max_items: usize,
+    #[serde(default)]
+    /// The maximum combined length of the items.
+    ///
+    /// Strings count their characters, any other value the length
+    /// of its JSON representation.
+    pub max_total_length: Option<usize>,
+    #[serde(default)]
+    /// Should every item be entered on a single line, split by the delimiter.
+    ///
+    /// This does not apply to arrays of objects.
+    pub inline: bool,
+    #[serde(default = "default_delimiter")]
+    /// The character separating inline items, a whitespace delimiter
+    /// splits on any run of whitespace.
+    pub delimiter: char,
+    #[serde(default)]
+    /// Should the items be entered in a text editor, one item per line.
+    ///
+    /// Blank lines and the instructions written above the items are
+    /// ignored, closing the editor unchanged accepts the items shown. This
+    /// does not apply to arrays of objects and takes precedence over
+    /// `inline`.
+    pub editor: bool,
+    #[serde(default)]
+    /// Can the items be moved up and down once they are entered.
+    ///
+    /// This only applies to items entered one at a time, not to arrays
+    /// of objects, and needs stdin to be a terminal.
+    pub allow_reorder: bool,
+    #[serde(default)]
+    /// The order numeric items must be entered in, i.e. the boundaries
+    /// of histogram buckets. Items which are not numbers are ignored.
+    pub monotonic: Option<Monotonic>,
+}
+
+impl CollectionConstraints {
+    /// Splits an inline input into its trimmed items.
+    pub fn split<'a>(&self, input: &'a str) -> Vec<&'a str> {
+        if input.trim().is_empty() {
+            Vec::new()
+        } else if self.delimiter.is_whitespace() {
+            input.split_whitespace().collect()
+        } else {
+            input.split(self.delimiter).map(str::trim).collect()
+        }
+    }
+
+    /// Checks the combined length of the items stays within `max_total_length`.
+    pub fn validate_total_length(&self, values: &[serde_json::Value]) -> Result<(), String> {
+        let max = match self.max_total_length {
+            None => return Ok(()),
+            Some(max) => max,
+        };
+
+        let total = values.iter().map(value_length).sum::<usize>();
+        if total > max {
+            return Err(format!(
+                "The combined length of the values ({total}) exceeds the maximum allowed ({max})"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks each numeric item follows the previous one in the `monotonic` order.
+    pub fn validate_order(&self, values: &[serde_json::Value]) -> Result<(), String> {
+        let monotonic = match self.monotonic {
+            None => return Ok(()),
+            Some(monotonic) => monotonic,
+        };
+
+        let numbers = values
+            .iter()
+            .filter_map(|value| Some((value, value.as_f64()?)));
+        let mut previous: Option<(&serde_json::Value, f64)> = None;
+        for (value, number) in numbers {
+            match previous {
+                Some((previous, previous_number)) if !monotonic.holds(previous_number, number) => {
+                    return Err(format!(
+                        "Value {value} is out of order after {previous}, the values must be \
+                         {monotonic}"
+                    ));
+                }
+                _ => previous = Some((value, number)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The length of a value counted towards `max_total_length`.
+fn value_length(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::String(s) => s.chars().count(),
+        value => value.to_string().len(),
+    }
 }
 
 impl Default for CollectionConstraints {
@@ -21,21 +123,215 @@ impl Default for CollectionConstraints {
         Self {
             min_items: 0,
             max_items: usize::MAX,
+            max_total_length: None,
+            inline: false,
+            delimiter: default_delimiter(),
+            editor: false,
+            allow_reorder: false,
+            monotonic: None,
         }
     }
 }
 
-#[derive(serde::Deserialize, Clone, Default)]
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+/// The order the items of an array must follow.
+pub enum Monotonic {
+    /// Each item is at least the previous one.
+    Increasing,
+    /// Each item is at most the previous one.
+    Decreasing,
+    /// Each item is greater than the previous one.
+    StrictlyIncreasing,
+    /// Each item is less than the previous one.
+    StrictlyDecreasing,
+}
+
+impl Monotonic {
+    /// Does the value follow the previous value in this order.
+    pub fn holds(&self, previous: f64, value: f64) -> bool {
+        match self {
+            Monotonic::Increasing => value >= previous,
+            Monotonic::Decreasing => value <= previous,
+            Monotonic::StrictlyIncreasing => value > previous,
+            Monotonic::StrictlyDecreasing => value < previous,
+        }
+    }
+}
+
+impl Display for Monotonic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Monotonic::Increasing => write!(f, "increasing"),
+            Monotonic::Decreasing => write!(f, "decreasing"),
+            Monotonic::StrictlyIncreasing => write!(f, "strictly increasing"),
+            Monotonic::StrictlyDecreasing => write!(f, "strictly decreasing"),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Default, PartialEq)]
 /// The constraints for the select type.
 pub struct SelectConstraints {
     #[serde(default)]
     /// Can several items be selected at once.
     pub select_many: bool,
+    #[serde(default)]
     /// The items that can be selected.
     pub items: Vec<serde_json::Value>,
+    #[serde(default)]
+    /// The dotted path of a previously answered array whose values are
+    /// added to the items, i.e. picking a primary from the hosts entered.
+    ///
+    /// The array must be answered and hold at least one value.
+    pub items_from_field: Option<String>,
+    #[serde(default)]
+    /// The index of the item which is highlighted when the menu opens.
+    ///
+    /// The item is marked as `(default)` in the menu so it can be
+    /// accepted by pressing Enter. Ignored when selecting many items.
+    pub default_index: Option<usize>,
+    #[serde(default)]
+    /// The item which is highlighted when the menu opens.
+    ///
+    /// This is matched against the items when prompting and takes
+    /// precedence over `default_index`.
+    pub default: Option<serde_json::Value>,
+    #[serde(default)]
+    /// A shell command whose output lines are added to the items.
+    ///
+    /// The command runs each time the menu is shown and requires the
+    /// `items-command` feature. It is not run when validating answers,
+    /// which accept any value for such a select.
+    pub items_command: Option<String>,
+    #[serde(default)]
+    /// Store the selected item under this key in an object, i.e.
+    /// `{"<tag>": <selected>}`.
+    ///
+    /// The object produced by a matching condition is stored alongside the
+    /// tag, while fields inserted at the root are unaffected.
+    pub tag: Option<String>,
+    #[serde(default)]
+    /// Items which cannot be picked given the previously populated fields,
+    /// when selecting many items.
+    ///
+    /// Disabled items are shown dimmed and a selection including one of
+    /// them is rejected.
+    pub disabled: Vec<DisabledItem>,
+    #[serde(default)]
+    /// The number of items visible at once, scrolling through the rest.
+    ///
+    /// If left blank the menu fits the height of the terminal.
+    pub page_size: Option<usize>,
+    #[serde(default)]
+    /// The maximum width of an item's label, longer labels are cut short
+    /// with `…` while the full item is still stored.
+    ///
+    /// If left blank the labels fit the width of the terminal.
+    pub max_label_width: Option<usize>,
+}
+
+#[derive(serde::Deserialize, Clone, PartialEq)]
+/// An item of a select which is disabled when a rule passes.
+pub struct DisabledItem {
+    /// The item to disable.
+    pub item: serde_json::Value,
+    /// The rule deciding if the item is disabled, written like `show_if`.
+    pub when: ShowIf,
+}
+
+impl SelectConstraints {
+    /// The items which can be selected, including the values of the
+    /// referenced field and the output of the items command if set.
+    pub fn resolve_items(
+        &self,
+        populated_fields: &BTreeMap<String, serde_json::Value>,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        let mut items = self.items.clone();
+        if let Some(source) = self.items_from_field.as_ref() {
+            match lookup_field(populated_fields, source) {
+                Some(serde_json::Value::Array(values)) if !values.is_empty() => {
+                    items.extend(values.iter().cloned())
+                }
+                Some(serde_json::Value::Array(_)) => {
+                    return Err(format!("The field {source:?} has no items to select from"))
+                }
+                None | Some(serde_json::Value::Null) => {
+                    return Err(format!("The field {source:?} has not been answered"))
+                }
+                Some(value) => {
+                    return Err(format!(
+                        "The field {source:?} is not an array, found {value}"
+                    ))
+                }
+            }
+        }
+        if let Some(command) = self.items_command.as_ref() {
+            items.extend(run_items_command(command)?);
+        }
+        Ok(items)
+    }
+
+    /// Checks no item is listed more than once, which would make the
+    /// selected item and the conditions it triggers ambiguous.
+    pub fn check_items(&self) -> Result<(), String> {
+        for (index, item) in self.items.iter().enumerate() {
+            if self.items[..index].contains(item) {
+                return Err(format!("The item {item} is listed more than once"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Is the item disabled given the values populated so far.
+    pub fn is_disabled(
+        &self,
+        item: &serde_json::Value,
+        populated_fields: &BTreeMap<String, serde_json::Value>,
+    ) -> bool {
+        self.disabled
+            .iter()
+            .any(|disabled| disabled.item == *item && disabled.when.matches(populated_fields))
+    }
+
+    /// The value to store for the selected item, given the object
+    /// produced by its condition if any.
+    pub fn to_value(
+        &self,
+        selected: serde_json::Value,
+        condition_value: Option<serde_json::Value>,
+    ) -> serde_json::Value {
+        let tag = match self.tag.as_ref() {
+            Some(tag) if !selected.is_null() => tag,
+            _ => return condition_value.unwrap_or(selected),
+        };
+
+        let mut object = match condition_value {
+            Some(serde_json::Value::Object(object)) => object,
+            _ => serde_json::Map::new(),
+        };
+        object.insert(tag.clone(), selected);
+        serde_json::Value::Object(object)
+    }
+
+    /// The index of the default item within the resolved items, if one is set.
+    ///
+    /// An error is returned if the default value is not one of the items.
+    pub fn resolve_default_index(
+        &self,
+        items: &[serde_json::Value],
+    ) -> Result<Option<usize>, String> {
+        match self.default.as_ref() {
+            Some(default) => match items.iter().position(|item| item == default) {
+                Some(index) => Ok(Some(index)),
+                None => Err(format!("Default value {default} is not one of the items")),
+            },
+            None => Ok(self.default_index.filter(|index| *index < items.len())),
+        }
+    }
 }
 
-#[derive(serde::Deserialize, Default)]
+#[derive(serde::Deserialize, Clone, Default, PartialEq)]
 /// Prompts which are triggered on value selection.
 pub struct Conditions {
     #[serde(default)]
@@ -44,120 +340,1723 @@ pub struct Conditions {
     /// If this is false (default) it will be inserted in a nested fashion.
     pub insert_at_root: bool,
 
-    #[serde(rename = "if")]
+    #[serde(rename = "if", default)]
     /// If conditions if a value is picked.
     ///
     /// Not triggered when value skipped.
     pub if_conditions: Vec<IfCondition>,
+
+    #[serde(default)]
+    /// The prompt fields to trigger when the value is skipped.
+    ///
+    /// They are inserted like the fields of an if condition, so unless
+    /// `insert_at_root` is set the skipped value becomes their object.
+    pub on_skip: Option<IndexMap<String, Field>>,
 }
 
-#[derive(serde::Deserialize)]
+impl Conditions {
+    /// The fields of every condition, including those prompted on skip.
+    pub(crate) fn all_fields(&self) -> impl Iterator<Item = &IndexMap<String, Field>> {
+        self.if_conditions
+            .iter()
+            .map(|condition| &condition.fields)
+            .chain(self.on_skip.iter())
+    }
+}
+
+#[derive(serde::Deserialize, Clone, PartialEq)]
 pub struct IfCondition {
     /// The trigger value.
     pub picked: serde_json::Value,
+    #[serde(default)]
+    /// An optional yes/no question asked before prompting the fields.
+    ///
+    /// If the user declines, the fields are skipped and nothing is stored
+    /// for them, leaving the picked value as the field's value.
+    pub confirm: Option<String>,
+    #[serde(default)]
+    /// For a multi-select, prompt the fields once after the selection is
+    /// made if the trigger value is among the selected items.
+    ///
+    /// Otherwise the fields are prompted as each selected item is handled.
+    /// Nested fields are stored with the trigger item either way.
+    pub once: bool,
     /// The prompt fields to trigger.
     pub fields: IndexMap<String, Field>,
 }
 
-#[derive(serde::Deserialize, Clone)]
-/// The constraints for string types.
-pub struct StringConstraints {
+#[derive(serde::Deserialize, Clone, PartialEq)]
+/// A rule deciding if a field is shown.
+pub struct ShowIf {
+    /// The key of the previously populated field to check.
+    ///
+    /// Nested values can be referenced with dots, i.e. `params.topic`.
+    pub field: String,
+    /// The value the field must have for the rule to pass.
+    pub equals: serde_json::Value,
+}
+
+impl ShowIf {
+    /// Does the rule pass given the values populated so far.
+    pub fn matches(&self, populated_fields: &BTreeMap<String, serde_json::Value>) -> bool {
+        lookup_field(populated_fields, &self.field) == Some(&self.equals)
+    }
+}
+
+#[derive(serde::Deserialize)]
+/// A rule which spans several fields, checked once every field is populated.
+pub struct CrossFieldConstraint {
+    /// The comparison which must hold, i.e. `end_date > start_date`.
+    ///
+    /// Each side is either a dotted field path or a JSON literal and is
+    /// compared with one of `==`, `!=`, `<`, `<=`, `>` or `>=`. Ordering
+    /// comparisons pass if either side is missing or `null`.
+    pub rule: Predicate,
     #[serde(default)]
-    /// The minimum length of the string.
-    pub min_length: usize,
-    #[serde(default = "<usize as TraitIntBounds>::max")]
-    /// The maximum length of the string.
-    pub max_length: usize,
+    /// The message shown when the rule does not hold.
+    pub message: Option<String>,
     #[serde(default)]
-    /// The required regex match.
-    pub regex: Option<String>,
+    /// The top level fields which are prompted again when the rule does
+    /// not hold.
+    ///
+    /// If left blank the fields referenced by the rule are prompted again.
+    pub reprompt: Vec<String>,
 }
 
-impl Default for StringConstraints {
-    fn default() -> Self {
-        Self {
-            min_length: 0,
-            max_length: usize::MAX,
-            regex: None,
+impl CrossFieldConstraint {
+    /// Checks the rule against the populated fields.
+    pub fn check(
+        &self,
+        populated_fields: &BTreeMap<String, serde_json::Value>,
+    ) -> Result<(), String> {
+        if self.rule.evaluate(populated_fields) {
+            return Ok(());
+        }
+
+        match self.message.as_ref() {
+            Some(message) => Err(message.clone()),
+            None => Err(format!("The rule {} does not hold", self.rule)),
+        }
+    }
+
+    /// The top level fields to prompt again when the rule does not hold.
+    pub fn fields_to_reprompt(&self) -> Vec<String> {
+        if !self.reprompt.is_empty() {
+            return self.reprompt.clone();
+        }
+
+        let mut fields = Vec::new();
+        for operand in [&self.rule.left, &self.rule.right] {
+            if let Operand::Field(path) = operand {
+                let key = path.split('.').next().unwrap_or(path).to_string();
+                if !fields.contains(&key) {
+                    fields.push(key);
+                }
+            }
         }
+        fields
     }
 }
 
-impl Validator<String> for StringConstraints {
-    type Err = String;
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(try_from = "String")]
+/// A comparison between two operands, i.e. `end_date > start_date`.
+pub struct Predicate {
+    /// The left hand side of the comparison.
+    pub left: Operand,
+    /// The comparison operator.
+    pub comparison: Comparison,
+    /// The right hand side of the comparison.
+    pub right: Operand,
+}
 
-    fn validate(&mut self, input: &String) -> Result<(), Self::Err> {
-        if input.len() < self.min_length {
-            return Err(format!(
-                "Value {input:?} does not meet the minimum required length ({})",
-                self.max_length
-            ));
+#[derive(Clone, Debug, PartialEq)]
+/// One side of a [Predicate].
+pub enum Operand {
+    /// The value of the field at the dotted path.
+    Field(String),
+    /// A literal JSON value.
+    Value(serde_json::Value),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The operator of a [Predicate].
+pub enum Comparison {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+impl Comparison {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
         }
+    }
+}
 
-        if input.len() > self.max_length {
-            return Err(format!(
-                "Value {input:?} exceeds the maximum allowed length ({})",
-                self.max_length
-            ));
+impl Predicate {
+    /// Evaluates the comparison against the populated fields.
+    pub fn evaluate(&self, populated_fields: &BTreeMap<String, serde_json::Value>) -> bool {
+        let resolve = |operand: &Operand| match operand {
+            Operand::Field(path) => lookup_field(populated_fields, path)
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+            Operand::Value(value) => value.clone(),
+        };
+        let left = resolve(&self.left);
+        let right = resolve(&self.right);
+
+        match self.comparison {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            _ if left.is_null() || right.is_null() => true,
+            comparison => match compare_values(&left, &right) {
+                None => false,
+                Some(ordering) => match comparison {
+                    Comparison::Lt => ordering == Ordering::Less,
+                    Comparison::Le => ordering != Ordering::Greater,
+                    Comparison::Gt => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                },
+            },
         }
+    }
+}
 
-        if let Some(re) = self.regex.as_ref() {
-            let regex =
-                Regex::new(re).map_err(|e| format!("Failed to build regex validator: {e}"))?;
+impl TryFrom<String> for Predicate {
+    type Error = String;
 
-            if !regex.is_match(input) {
-                return Err(format!(
-                    "Value {input:?} does not match regex pattern: {re:?}"
-                ));
+    fn try_from(rule: String) -> Result<Self, Self::Error> {
+        // Two character operators are checked first so `<=` is not read as `<`.
+        let comparisons = [
+            Comparison::Le,
+            Comparison::Ge,
+            Comparison::Eq,
+            Comparison::Ne,
+            Comparison::Lt,
+            Comparison::Gt,
+        ];
+
+        let mut in_quotes = false;
+        let mut previous = None;
+        for (position, c) in rule.char_indices() {
+            if c == '"' && previous != Some('\\') {
+                in_quotes = !in_quotes;
             }
+            previous = Some(c);
+            if in_quotes {
+                continue;
+            }
+
+            let rest = &rule[position..];
+            let comparison = match comparisons.iter().find(|c| rest.starts_with(c.symbol())) {
+                None => continue,
+                Some(comparison) => *comparison,
+            };
+
+            let left = parse_operand(&rule[..position])
+                .ok_or_else(|| format!("Rule {rule:?} has an invalid left hand side"))?;
+            let right = parse_operand(&rest[comparison.symbol().len()..])
+                .ok_or_else(|| format!("Rule {rule:?} has an invalid right hand side"))?;
+            return Ok(Self {
+                left,
+                comparison,
+                right,
+            });
         }
 
-        Ok(())
+        Err(format!("Rule {rule:?} does not contain a comparison"))
     }
 }
 
-#[derive(serde::Deserialize, Clone, Copy)]
-/// The constraints for integer types.
-pub struct IntConstraints<T: TraitIntBounds + Clone + Copy> {
+impl Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operand = |operand: &Operand| match operand {
+            Operand::Field(path) => path.clone(),
+            Operand::Value(value) => value.to_string(),
+        };
+        write!(
+            f,
+            "{} {} {}",
+            operand(&self.left),
+            self.comparison.symbol(),
+            operand(&self.right)
+        )
+    }
+}
+
+/// Parses a literal JSON value, or otherwise a dotted field path.
+fn parse_operand(operand: &str) -> Option<Operand> {
+    let operand = operand.trim();
+    if let Ok(value) = serde_json::from_str(operand) {
+        return Some(Operand::Value(value));
+    }
+
+    let is_path = !operand.is_empty()
+        && operand.split('.').all(|key| {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        });
+    is_path.then(|| Operand::Field(operand.to_string()))
+}
+
+/// Orders two numbers or two strings, other values cannot be ordered.
+fn compare_values(left: &serde_json::Value, right: &serde_json::Value) -> Option<Ordering> {
+    match (left, right) {
+        (serde_json::Value::Number(left), serde_json::Value::Number(right)) => {
+            left.as_f64()?.partial_cmp(&right.as_f64()?)
+        }
+        (serde_json::Value::String(left), serde_json::Value::String(right)) => {
+            Some(left.cmp(right))
+        }
+        _ => None,
+    }
+}
+
+/// The most memory, in bytes, a compiled regex may use.
+pub static REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Compiles the pattern, failing if it exceeds [REGEX_SIZE_LIMIT].
+fn build_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+}
+
+/// Looks up the value at the dotted path within the populated fields.
+pub(crate) fn lookup_field<'a>(
+    populated_fields: &'a BTreeMap<String, serde_json::Value>,
+    path: &str,
+) -> Option<&'a serde_json::Value> {
+    let mut path = path.split('.');
+    path.next()
+        .and_then(|key| populated_fields.get(key))
+        .and_then(|value| path.try_fold(value, |value, key| value.get(key)))
+}
+
+#[derive(serde::Deserialize, Clone, PartialEq)]
+/// The constraints for boolean types.
+pub struct BoolConstraints {
     #[serde(default)]
-    /// The minimum value allowed.
-    pub min: T,
-    #[serde(default = "<T as TraitIntBounds>::max")]
-    /// The maximum value allowed.
-    pub max: T,
+    /// Should the answer be typed rather than picked with a yes/no confirm.
+    ///
+    /// Answers are always typed when stdin is not a terminal.
+    pub text_entry: bool,
+    #[serde(default = "default_truthy")]
+    /// The typed answers which mean `true`, matched case insensitively.
+    pub truthy: Vec<String>,
+    #[serde(default = "default_falsey")]
+    /// The typed answers which mean `false`, matched case insensitively.
+    pub falsey: Vec<String>,
 }
 
-impl<T: TraitIntBounds + Clone + Copy> Default for IntConstraints<T> {
+impl Default for BoolConstraints {
     fn default() -> Self {
         Self {
-            min: T::min(),
-            max: T::max(),
+            text_entry: false,
+            truthy: default_truthy(),
+            falsey: default_falsey(),
+        }
+    }
+}
+
+impl BoolConstraints {
+    /// Parses a typed answer into a boolean.
+    pub fn parse(&self, input: &str) -> Result<bool, String> {
+        let input = input.trim();
+        let matches =
+            |tokens: &[String]| tokens.iter().any(|token| token.eq_ignore_ascii_case(input));
+        if matches(&self.truthy) {
+            Ok(true)
+        } else if matches(&self.falsey) {
+            Ok(false)
+        } else {
+            Err(format!(
+                "Value {input:?} is not one of {:?} or {:?}",
+                self.truthy, self.falsey
+            ))
         }
     }
 }
 
-impl<T: TraitIntBounds + Clone + Copy> Validator<T> for IntConstraints<T> {
+impl Validator<String> for BoolConstraints {
     type Err = String;
 
-    fn validate(&mut self, input: &T) -> Result<(), Self::Err> {
-        if input < &self.min {
-            return Err(format!("Value {input:?} cannot be less than {}", self.min));
-        }
+    fn validate(&mut self, input: &String) -> Result<(), Self::Err> {
+        self.parse(input).map(|_| ())
+    }
+}
+
+fn default_truthy() -> Vec<String> {
+    ["y", "yes", "true", "1", "on"].map(String::from).to_vec()
+}
+
+fn default_falsey() -> Vec<String> {
+    ["n", "no", "false", "0", "off"].map(String::from).to_vec()
+}
+
+#[derive(serde::Deserialize, Clone, PartialEq)]
+/// The constraints for string types.
+pub struct StringConstraints {
+    #[serde(default)]
+    /// The minimum length of the string in characters.
+    pub min_length: usize,
+    #[serde(default = "<usize as TraitIntBounds>::max")]
+    /// The maximum length of the string in characters.
+    pub max_length: usize,
+    #[serde(default)]
+    /// The minimum size of the string in UTF-8 bytes.
+    pub min_bytes: Option<usize>,
+    #[serde(default)]
+    /// The maximum size of the string in UTF-8 bytes, i.e. the size
+    /// of the column it is stored in.
+    pub max_bytes: Option<usize>,
+    #[serde(default)]
+    /// The required regex match.
+    pub regex: Option<String>,
+    #[serde(default)]
+    /// The values which are allowed, if empty any value is allowed.
+    pub allowed: Vec<String>,
+    #[serde(default)]
+    /// The values which are reserved and cannot be used.
+    pub denied: Vec<String>,
+    #[serde(default = "default_true")]
+    /// Should the allowed and denied values be matched case sensitively.
+    pub case_sensitive: bool,
+    #[serde(default)]
+    /// A template the value must follow, i.e. `"(###) ###-####"`.
+    ///
+    /// `#` matches a digit, `@` matches a letter and `*` matches either,
+    /// any other character is a literal. The user can enter the value
+    /// with or without the literal characters.
+    pub mask: Option<String>,
+    #[serde(default)]
+    /// Should only the characters matching the mask placeholders be stored
+    /// rather than the formatted value.
+    pub store_raw: bool,
+    #[serde(default)]
+    /// Should any character outside of ASCII be rejected.
+    pub ascii_only: bool,
+    #[serde(default)]
+    /// The language the value must be a valid identifier in.
+    pub identifier: Option<Lang>,
+    #[serde(default)]
+    /// Rules which warn about the value without rejecting it.
+    pub warnings: Vec<StringWarning>,
+    #[serde(default)]
+    /// The transformations applied in order to the input, before it is
+    /// validated and stored.
+    pub transforms: Vec<Transform>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// A transformation applied to a string input.
+pub enum Transform {
+    /// Removes leading and trailing whitespace.
+    Trim,
+    /// Converts the value to lowercase.
+    Lower,
+    /// Converts the value to uppercase.
+    Upper,
+    /// Converts the value to a lowercase slug, i.e. `"My Project"` becomes
+    /// `"my-project"`.
+    Slug,
+    /// Replaces every occurrence of `from` with `to`.
+    Replace {
+        /// The text to replace.
+        from: String,
+        /// The replacement text.
+        to: String,
+    },
+}
 
-        if input > &self.max {
-            return Err(format!("Value {input:?} must be less than {}", self.max));
+impl Transform {
+    /// Applies the transformation to the value.
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            Transform::Trim => value.trim().to_string(),
+            Transform::Lower => value.to_lowercase(),
+            Transform::Upper => value.to_uppercase(),
+            Transform::Slug => value
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join("-"),
+            Transform::Replace { from, to } => value.replace(from.as_str(), to),
         }
+    }
+}
 
-        Ok(())
+#[derive(serde::Deserialize, Clone, PartialEq)]
+/// A rule which warns about a string value without rejecting it.
+pub struct StringWarning {
+    /// The regex which triggers the warning when it matches the value.
+    pub regex: String,
+    /// The warning to show.
+    pub message: String,
+}
+
+impl Default for StringConstraints {
+    fn default() -> Self {
+        Self {
+            min_length: 0,
+            max_length: usize::MAX,
+            min_bytes: None,
+            max_bytes: None,
+            regex: None,
+            allowed: Vec::new(),
+            denied: Vec::new(),
+            case_sensitive: true,
+            mask: None,
+            store_raw: false,
+            ascii_only: false,
+            identifier: None,
+            warnings: Vec::new(),
+            transforms: Vec::new(),
+        }
     }
 }
 
-/// A no-op validator.
-pub struct BlankValidator;
-impl<T> Validator<T> for BlankValidator {
-    type Err = String;
+impl StringConstraints {
+    /// Converts the value into the JSON value which should be stored.
+    pub fn to_value(&self, input: String) -> serde_json::Value {
+        let input = self.transform(&input);
+        match self.apply_mask(&input) {
+            Ok(Some(value)) => serde_json::Value::from(value),
+            _ => serde_json::Value::from(input),
+        }
+    }
 
-    fn validate(&mut self, _input: &T) -> Result<(), Self::Err> {
-        Ok(())
+    /// A sample value which satisfies the constraints, if one of a few
+    /// simple candidates does.
+    pub fn example(&self) -> serde_json::Value {
+        self.to_value(self.example_input())
+    }
+
+    /// The `index`th of a sequence of distinct sample values, see
+    /// [StringConstraints::example].
+    ///
+    /// Later values end with their index, i.e. `example1`, when that is
+    /// still valid.
+    pub fn nth_example(&self, index: usize) -> serde_json::Value {
+        if let Some(allowed) = self.allowed.get(index) {
+            return self.to_value(allowed.clone());
+        }
+
+        let input = self.example_input();
+        if index == 0 {
+            return self.to_value(input);
+        }
+
+        let suffix = index.to_string();
+        let kept = input.chars().count().saturating_sub(suffix.len());
+        let candidates = [
+            format!("{input}{suffix}"),
+            format!("{}{suffix}", input.chars().take(kept).collect::<String>()),
+        ];
+        let input = candidates
+            .iter()
+            .find(|candidate| self.clone().validate(candidate).is_ok())
+            .unwrap_or(&candidates[0]);
+        self.to_value(input.clone())
+    }
+
+    fn example_input(&self) -> String {
+        if let Some(allowed) = self.allowed.first() {
+            return allowed.clone();
+        }
+
+        if let Some(mask) = self.mask.as_ref() {
+            return mask
+                .chars()
+                .map(|c| match c {
+                    '#' => '0',
+                    '@' | '*' => 'a',
+                    c => c,
+                })
+                .collect();
+        }
+
+        let length = self.min_length.max(1);
+        let candidates = [
+            format!("{:a<length$}", "example"),
+            "a".repeat(length),
+            "0".repeat(length),
+            String::new(),
+        ];
+        let example = candidates
+            .iter()
+            .find(|candidate| self.clone().validate(candidate).is_ok())
+            .unwrap_or(&candidates[0]);
+        example.clone()
+    }
+
+    /// Applies the transformations to the input in order.
+    pub fn transform(&self, input: &str) -> String {
+        self.transforms
+            .iter()
+            .fold(input.to_string(), |value, transform| {
+                transform.apply(&value)
+            })
+    }
+
+    /// Applies the mask to the input, returning the value to store.
+    fn apply_mask(&self, input: &str) -> Result<Option<String>, String> {
+        let mask = match self.mask.as_ref() {
+            None => return Ok(None),
+            Some(mask) => mask,
+        };
+
+        let is_placeholder = |c: char| matches!(c, '#' | '@' | '*');
+        let follows_mask = input.chars().count() == mask.chars().count()
+            && input
+                .chars()
+                .zip(mask.chars())
+                .all(|(c, slot)| is_placeholder(slot) || c == slot);
+
+        // Input which already follows the mask keeps its placeholder characters,
+        // otherwise separators and whitespace are ignored.
+        let raw = if follows_mask {
+            input
+                .chars()
+                .zip(mask.chars())
+                .filter(|(_, slot)| is_placeholder(*slot))
+                .map(|(c, _)| c)
+                .collect::<Vec<_>>()
+        } else {
+            input
+                .chars()
+                .filter(|c| !c.is_whitespace() && (c.is_alphanumeric() || !mask.contains(*c)))
+                .collect::<Vec<_>>()
+        };
+        let mut raw = raw.into_iter();
+
+        let mut formatted = String::new();
+        let mut stripped = String::new();
+        for (position, slot) in mask.chars().enumerate() {
+            if !is_placeholder(slot) {
+                formatted.push(slot);
+                continue;
+            }
+
+            let c = raw
+                .next()
+                .ok_or_else(|| format!("Value {input:?} is too short for the format {mask:?}"))?;
+            let (is_valid, expected) = match slot {
+                '#' => (c.is_ascii_digit(), "a digit"),
+                '@' => (c.is_alphabetic(), "a letter"),
+                _ => (c.is_alphanumeric(), "a letter or digit"),
+            };
+            if !is_valid {
+                return Err(format!(
+                    "Character {c:?} is not valid at position {} of the format {mask:?}, expected \
+                     {expected}",
+                    position + 1
+                ));
+            }
+
+            formatted.push(c);
+            stripped.push(c);
+        }
+
+        if raw.next().is_some() {
+            return Err(format!(
+                "Value {input:?} is too long for the format {mask:?}"
+            ));
+        }
+
+        Ok(Some(if self.store_raw { stripped } else { formatted }))
+    }
+
+    /// The warnings triggered by an accepted value.
+    ///
+    /// A warning whose regex cannot be built is itself returned as a warning.
+    pub fn warnings(&self, input: &str) -> Vec<String> {
+        self.warnings
+            .iter()
+            .filter_map(|warning| match build_regex(&warning.regex) {
+                Ok(regex) if regex.is_match(input) => Some(warning.message.clone()),
+                Ok(_) => None,
+                Err(e) => Some(format!("Failed to build warning regex: {e}")),
+            })
+            .collect()
+    }
+
+    /// Checks every regex of the constraints can be built.
+    pub fn check_patterns(&self) -> Result<(), String> {
+        if let Some(re) = self.regex.as_ref() {
+            build_regex(re).map_err(|e| format!("The regex {re:?} cannot be built: {e}"))?;
+        }
+        for warning in self.warnings.iter() {
+            build_regex(&warning.regex).map_err(|e| {
+                format!("The warning regex {:?} cannot be built: {e}", warning.regex)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn matches_any(&self, input: &str, values: &[String]) -> bool {
+        if self.case_sensitive {
+            values.iter().any(|value| value == input)
+        } else {
+            values
+                .iter()
+                .any(|value| value.to_lowercase() == input.to_lowercase())
+        }
+    }
+}
+
+impl Validator<String> for StringConstraints {
+    type Err = String;
+
+    fn validate(&mut self, input: &String) -> Result<(), Self::Err> {
+        let transformed = self.transform(input);
+        let masked = self.apply_mask(&transformed)?;
+        let input = masked.as_ref().unwrap_or(&transformed);
+
+        let length = input.chars().count();
+        if length < self.min_length {
+            return Err(format!(
+                "Value {input:?} does not meet the minimum required length ({})",
+                self.min_length
+            ));
+        }
+
+        if length > self.max_length {
+            return Err(format!(
+                "Value {input:?} exceeds the maximum allowed length ({})",
+                self.max_length
+            ));
+        }
+
+        if let Some(min) = self.min_bytes.filter(|min| input.len() < *min) {
+            return Err(format!(
+                "Value {input:?} is {} bytes, less than the minimum of {min} bytes",
+                input.len()
+            ));
+        }
+
+        if let Some(max) = self.max_bytes.filter(|max| input.len() > *max) {
+            return Err(format!(
+                "Value {input:?} is {} bytes, which exceeds {max} bytes",
+                input.len()
+            ));
+        }
+
+        if let Some((index, c)) = input
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii())
+            .filter(|_| self.ascii_only)
+        {
+            return Err(format!(
+                "Value {input:?} must only contain ASCII characters, found {c:?} at position {}",
+                index + 1
+            ));
+        }
+
+        if let Some(lang) = self.identifier {
+            lang.validate_identifier(input).map_err(|reason| {
+                format!("Value {input:?} is not a valid {lang} identifier: {reason}")
+            })?;
+        }
+
+        if let Some(re) = self.regex.as_ref() {
+            let regex =
+                build_regex(re).map_err(|e| format!("Failed to build regex validator: {e}"))?;
+
+            if !regex.is_match(input) {
+                return Err(format!(
+                    "Value {input:?} does not match regex pattern: {re:?}"
+                ));
+            }
+        }
+
+        if !self.allowed.is_empty() && !self.matches_any(input, &self.allowed) {
+            return Err(format!(
+                "Value {input:?} is not one of the allowed values: {:?}",
+                self.allowed
+            ));
+        }
+
+        if self.matches_any(input, &self.denied) {
+            return Err(format!("Value {input:?} is reserved and cannot be used"));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Copy, PartialEq)]
+/// The constraints for integer types.
+pub struct IntConstraints<T: TraitIntBounds + Clone + Copy> {
+    #[serde(default)]
+    /// The minimum value allowed.
+    pub min: T,
+    #[serde(default = "<T as TraitIntBounds>::max")]
+    /// The maximum value allowed.
+    pub max: T,
+    #[serde(default)]
+    /// The radix numbers are shown in within messages.
+    ///
+    /// The value is always stored as a decimal number.
+    pub display_radix: Radix,
+    #[serde(default)]
+    /// The smallest recommended value, smaller values are accepted
+    /// with a warning.
+    pub recommended_min: Option<T>,
+    #[serde(default)]
+    /// The largest recommended value, larger values are accepted
+    /// with a warning.
+    pub recommended_max: Option<T>,
+    #[serde(default)]
+    /// Should the allowed values be picked from a menu rather than typed.
+    ///
+    /// Ranges of more than [MAX_MENU_ITEMS] values are always typed.
+    pub as_menu: bool,
+    #[serde(default)]
+    /// Pick the value from a menu whenever the range has at most
+    /// this many values.
+    pub menu_threshold: Option<usize>,
+    #[serde(default)]
+    /// Should the allowed range be shown after the prompt, i.e. `Replicas [1, 10]`.
+    pub show_range: bool,
+}
+
+/// The most values an integer range is shown as a menu for.
+pub static MAX_MENU_ITEMS: usize = 100;
+
+impl<T: TraitIntBounds + Clone + Copy> Default for IntConstraints<T> {
+    fn default() -> Self {
+        Self {
+            min: T::min(),
+            max: T::max(),
+            display_radix: Radix::default(),
+            recommended_min: None,
+            recommended_max: None,
+            as_menu: false,
+            menu_threshold: None,
+            show_range: false,
+        }
+    }
+}
+
+impl<T: TraitIntBounds + Clone + Copy + Into<i128>> IntConstraints<T> {
+    /// The `index`th allowed value counting up from the minimum, or the
+    /// maximum once the range runs out.
+    pub fn nth_example(&self, index: usize) -> T
+    where
+        T: TryFrom<i128>,
+    {
+        let value = self.min.into().saturating_add(index as i128);
+        T::try_from(value.min(self.max.into())).unwrap_or(self.max)
+    }
+
+    /// The allowed range shown after the prompt, if `show_range` is set.
+    pub fn range_hint(&self) -> Option<String> {
+        if !self.show_range {
+            return None;
+        }
+
+        let radix = self.display_radix;
+        Some(range(
+            radix.format(self.min),
+            radix.format(self.max),
+            false,
+            false,
+        ))
+    }
+
+    /// The values to pick from if the range is shown as a menu.
+    pub fn menu_items(&self) -> Option<Vec<i128>> {
+        let (min, max) = (self.min.into(), self.max.into());
+        let len = usize::try_from(max - min + 1).ok()?;
+        let threshold = self.menu_threshold.unwrap_or(0);
+        if len == 0 || len > MAX_MENU_ITEMS || (!self.as_menu && len > threshold) {
+            return None;
+        }
+
+        Some((min..=max).collect())
+    }
+
+    /// The warning for an accepted value outside of the recommended range.
+    pub fn warning(&self, input: T) -> Option<String> {
+        let below = self.recommended_min.is_some_and(|min| input < min);
+        let above = self.recommended_max.is_some_and(|max| input > max);
+        if !below && !above {
+            return None;
+        }
+
+        let radix = self.display_radix;
+        Some(format!(
+            "Value {} is outside the recommended range [{}, {}]",
+            radix.format(input),
+            radix.format(self.recommended_min.unwrap_or(self.min)),
+            radix.format(self.recommended_max.unwrap_or(self.max)),
+        ))
+    }
+}
+
+impl<T: TraitIntBounds + Clone + Copy + Into<i128>> Validator<T> for IntConstraints<T> {
+    type Err = String;
+
+    fn validate(&mut self, input: &T) -> Result<(), Self::Err> {
+        if input < &self.min || input > &self.max {
+            let radix = self.display_radix;
+            return Err(out_of_range(
+                radix.format(*input),
+                radix.format(self.min),
+                radix.format(self.max),
+                false,
+                false,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// The radix an integer is displayed in.
+pub enum Radix {
+    #[default]
+    /// Base 10, i.e. `255`.
+    Decimal,
+    /// Base 16, i.e. `0xff`.
+    Hex,
+    /// Base 8, i.e. `0o377`.
+    Octal,
+    /// Base 2, i.e. `0b11111111`.
+    Binary,
+}
+
+impl Radix {
+    /// Formats the integer with the radix prefix.
+    pub fn format(&self, value: impl Into<i128>) -> String {
+        let value = value.into();
+        let sign = if value < 0 { "-" } else { "" };
+        let magnitude = value.unsigned_abs();
+        match self {
+            Radix::Decimal => value.to_string(),
+            Radix::Hex => format!("{sign}{magnitude:#x}"),
+            Radix::Octal => format!("{sign}{magnitude:#o}"),
+            Radix::Binary => format!("{sign}{magnitude:#b}"),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// A programming language whose identifiers a string must be valid in.
+///
+/// Only ASCII identifiers are accepted.
+pub enum Lang {
+    /// A Rust identifier, which cannot be a keyword or `_`.
+    Rust,
+    /// A Python identifier, which cannot be a keyword.
+    Python,
+}
+
+impl Lang {
+    /// The words which cannot be used as identifiers.
+    pub fn reserved_words(&self) -> &'static [&'static str] {
+        match self {
+            Lang::Rust => &[
+                "_", "abstract", "as", "async", "await", "become", "box", "break", "const",
+                "continue", "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn",
+                "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move",
+                "mut", "override", "priv", "pub", "ref", "return", "self", "Self", "static",
+                "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+                "use", "virtual", "where", "while", "yield",
+            ],
+            Lang::Python => &[
+                "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+                "continue", "def", "del", "elif", "else", "except", "finally", "for", "from",
+                "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass",
+                "raise", "return", "try", "while", "with", "yield",
+            ],
+        }
+    }
+
+    /// Checks the name is a valid identifier, returning why it is not.
+    pub fn validate_identifier(&self, name: &str) -> Result<(), String> {
+        let first = name.chars().next().ok_or("it is empty")?;
+        if first.is_ascii_digit() {
+            return Err("it starts with a digit".to_string());
+        }
+
+        if let Some(c) = name
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+        {
+            return Err(format!(
+                "{c:?} is not allowed, only letters, digits and `_` are"
+            ));
+        }
+
+        if self.reserved_words().contains(&name) {
+            return Err("it is a reserved word".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Lang {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lang::Rust => f.write_str("Rust"),
+            Lang::Python => f.write_str("Python"),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Copy, PartialEq)]
+/// The constraints for float types.
+pub struct FloatConstraints<T: TraitIntBounds + Clone + Copy> {
+    #[serde(default = "<T as TraitIntBounds>::min")]
+    /// The minimum value allowed.
+    pub min: T,
+    #[serde(default = "<T as TraitIntBounds>::max")]
+    /// The maximum value allowed.
+    pub max: T,
+    #[serde(default)]
+    /// Should the minimum value itself be rejected.
+    pub exclusive_min: bool,
+    #[serde(default)]
+    /// Should the maximum value itself be rejected.
+    pub exclusive_max: bool,
+    #[serde(default)]
+    /// The number of decimal places the value is rounded to when stored.
+    ///
+    /// Validation is applied to the rounded value.
+    pub decimal_places: Option<u8>,
+    #[serde(default)]
+    /// Should the value be stored as a formatted string rather than a number.
+    ///
+    /// This avoids any float representation drift when combined with
+    /// `decimal_places`.
+    pub store_as_string: bool,
+    #[serde(default)]
+    /// The maximum number of decimal places which can be entered.
+    ///
+    /// Unlike `decimal_places` the value is rejected rather than rounded.
+    /// Trailing zeros are not counted.
+    pub max_decimal_places: Option<u8>,
+    #[serde(default)]
+    /// Should the allowed range be shown after the prompt, i.e.
+    /// `Ratio (0, 1]` with exclusive bounds in parentheses.
+    pub show_range: bool,
+}
+
+impl<T: TraitIntBounds + Clone + Copy> Default for FloatConstraints<T> {
+    fn default() -> Self {
+        Self {
+            min: T::min(),
+            max: T::max(),
+            exclusive_min: false,
+            exclusive_max: false,
+            decimal_places: None,
+            store_as_string: false,
+            max_decimal_places: None,
+            show_range: false,
+        }
+    }
+}
+
+impl<T> FloatConstraints<T>
+where
+    T: TraitIntBounds + Clone + Copy + Into<f64> + Into<serde_json::Value>,
+{
+    /// Rounds the value to the configured number of decimal places.
+    pub fn round(&self, value: f64) -> f64 {
+        match self.decimal_places {
+            Some(places) => {
+                let factor = 10f64.powi(places as i32);
+                (value * factor).round() / factor
+            }
+            None => value,
+        }
+    }
+
+    /// A sample value which satisfies the constraints.
+    ///
+    /// This is the minimum, or zero if there is no minimum.
+    pub fn example(&self) -> serde_json::Value {
+        self.nth_example(0)
+    }
+
+    /// The allowed range shown after the prompt, if `show_range` is set.
+    ///
+    /// A side without a bound is shown as infinite.
+    pub fn range_hint(&self) -> Option<String> {
+        if !self.show_range {
+            return None;
+        }
+
+        let (min, exclusive_min) = if self.min == T::min() {
+            ("-∞".to_string(), true)
+        } else {
+            (self.min.to_string(), self.exclusive_min)
+        };
+        let (max, exclusive_max) = if self.max == T::max() {
+            ("∞".to_string(), true)
+        } else {
+            (self.max.to_string(), self.exclusive_max)
+        };
+        Some(range(min, max, exclusive_min, exclusive_max))
+    }
+
+    /// The `index`th of a sequence of increasing sample values, see
+    /// [FloatConstraints::example].
+    ///
+    /// Values step up by one, closing in on the maximum rather than
+    /// passing it.
+    pub fn nth_example(&self, index: usize) -> serde_json::Value {
+        let min: f64 = self.min.into();
+        let max: f64 = self.max.into();
+        let mut value = if self.min == T::min() {
+            0f64.min(max)
+        } else {
+            min
+        };
+        if self.exclusive_min && value <= min {
+            value = if self.max == T::max() {
+                min + 1.0
+            } else {
+                (min + max) / 2.0
+            };
+        }
+
+        // Whole steps are taken while they stay below the maximum, then
+        // the values close in on it without reaching it.
+        let steps = (max - value).ceil();
+        if steps > 0.0 {
+            let index = index as f64;
+            if index < steps {
+                value += index;
+            } else {
+                let last = value + steps - 1.0;
+                let past = index - steps + 1.0;
+                value = last + (max - last) * past / (past + 1.0);
+            }
+        }
+
+        if self.store_as_string {
+            serde_json::Value::from(value.to_string())
+        } else {
+            serde_json::Value::from(value)
+        }
+    }
+
+    /// Converts the value into the JSON value which should be stored.
+    pub fn to_value(&self, value: T) -> serde_json::Value {
+        match (self.decimal_places, self.store_as_string) {
+            (Some(places), true) => {
+                let rounded = self.round(value.into());
+                serde_json::Value::from(format!("{rounded:.*}", places as usize))
+            }
+            (None, true) => serde_json::Value::from(value.to_string()),
+            (Some(_), false) => serde_json::Value::from(self.round(value.into())),
+            (None, false) => value.into(),
+        }
+    }
+}
+
+impl<T> Validator<T> for FloatConstraints<T>
+where
+    T: TraitIntBounds + Clone + Copy + Into<f64> + Into<serde_json::Value>,
+{
+    type Err = String;
+
+    fn validate(&mut self, input: &T) -> Result<(), Self::Err> {
+        // Typed input is checked before it is parsed, this catches values
+        // given as JSON or written in exponent notation.
+        check_decimal_places(&input.to_string(), self.max_decimal_places)?;
+
+        let value = self.round((*input).into());
+        let min: f64 = self.min.into();
+        let max: f64 = self.max.into();
+
+        let below_min = value < min || (self.exclusive_min && value <= min);
+        let above_max = value > max || (self.exclusive_max && value >= max);
+        if below_min || above_max {
+            // The default float bounds are too long to be useful in a message.
+            let bound = |bound: T, unbounded: T, label: &str| {
+                if bound == unbounded {
+                    label.to_string()
+                } else {
+                    bound.to_string()
+                }
+            };
+            return Err(out_of_range(
+                format!("{input:?}"),
+                bound(self.min, T::min(), "-inf"),
+                bound(self.max, T::max(), "inf"),
+                self.exclusive_min,
+                self.exclusive_max,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the number as written has at most `max` decimal places,
+/// not counting trailing zeros.
+pub(crate) fn check_decimal_places(input: &str, max: Option<u8>) -> Result<(), String> {
+    let max = match max {
+        None => return Ok(()),
+        Some(max) => max,
+    };
+
+    let mantissa = input.split(['e', 'E']).next().unwrap_or(input);
+    let places = mantissa
+        .split_once('.')
+        .map_or(0, |(_, decimals)| decimals.trim_end_matches('0').len());
+    if places > max as usize {
+        return Err(format!(
+            "Value {input} has {places} decimal places, at most {max} are allowed"
+        ));
+    }
+    Ok(())
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Default, PartialEq)]
+/// The constraints for byte size types.
+///
+/// Bounds can be given as a plain number of bytes or with a unit, i.e. `512MiB`.
+pub struct ByteSizeConstraints {
+    #[serde(default)]
+    /// The minimum size allowed.
+    pub min: Option<ByteSize>,
+    #[serde(default)]
+    /// The maximum size allowed.
+    pub max: Option<ByteSize>,
+}
+
+impl Validator<ByteSize> for ByteSizeConstraints {
+    type Err = String;
+
+    fn validate(&mut self, input: &ByteSize) -> Result<(), Self::Err> {
+        if let Some(min) = self.min.filter(|min| input < min) {
+            return Err(format!("Value {input} cannot be less than {min}"));
+        }
+
+        if let Some(max) = self.max.filter(|max| input > max) {
+            return Err(format!("Value {input} cannot be greater than {max}"));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Deserialize, Clone, PartialEq)]
+/// The constraints for money types.
+pub struct MoneyConstraints {
+    #[serde(default)]
+    /// The currency symbol which may surround the amount, i.e. `"$"`.
+    pub symbol: Option<String>,
+    #[serde(default)]
+    /// The currency code stored alongside the amount, i.e. `"USD"`.
+    pub currency: Option<String>,
+    #[serde(default = "default_decimal_places")]
+    /// The number of decimal places in a minor unit.
+    pub decimal_places: u8,
+    #[serde(default = "default_thousands_separator")]
+    /// The separator which can group the thousands.
+    pub thousands_separator: char,
+    #[serde(default = "default_decimal_separator")]
+    /// The separator between the major and minor units.
+    pub decimal_separator: char,
+    #[serde(default)]
+    /// The minimum amount allowed in minor units.
+    pub min: Option<i64>,
+    #[serde(default)]
+    /// The maximum amount allowed in minor units.
+    pub max: Option<i64>,
+}
+
+impl Default for MoneyConstraints {
+    fn default() -> Self {
+        Self {
+            symbol: None,
+            currency: None,
+            decimal_places: default_decimal_places(),
+            thousands_separator: default_thousands_separator(),
+            decimal_separator: default_decimal_separator(),
+            min: None,
+            max: None,
+        }
+    }
+}
+
+impl MoneyConstraints {
+    /// Parses the input into a number of minor units, i.e. `"$1,299.99"` into `129999`.
+    pub fn parse(&self, input: &str) -> Result<i64, String> {
+        let invalid = || format!("Value {input:?} is not a valid amount");
+
+        let mut amount = input.trim();
+        let is_negative = amount.starts_with('-');
+        amount = amount.trim_start_matches('-').trim();
+        if let Some(symbol) = self.symbol.as_deref().filter(|symbol| !symbol.is_empty()) {
+            amount = amount.strip_prefix(symbol).unwrap_or(amount);
+            amount = amount.strip_suffix(symbol).unwrap_or(amount).trim();
+        }
+
+        let amount = amount.replace(self.thousands_separator, "");
+        let (major, minor) = match amount.split_once(self.decimal_separator) {
+            None => (amount.as_str(), ""),
+            Some(parts) => parts,
+        };
+
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (major.is_empty() && minor.is_empty()) || !is_digits(major) || !is_digits(minor) {
+            return Err(invalid());
+        }
+
+        if minor.len() > self.decimal_places as usize {
+            return Err(format!(
+                "Value {input:?} cannot have more than {} decimal places",
+                self.decimal_places
+            ));
+        }
+
+        let scale = 10i64
+            .checked_pow(self.decimal_places as u32)
+            .ok_or_else(invalid)?;
+        let padding = 10i64.pow((self.decimal_places as usize - minor.len()) as u32);
+        let major = match major {
+            "" => 0,
+            major => major.parse::<i64>().map_err(|_| invalid())?,
+        };
+        let minor = match minor {
+            "" => 0,
+            minor => minor.parse::<i64>().map_err(|_| invalid())? * padding,
+        };
+
+        let total = major
+            .checked_mul(scale)
+            .and_then(|major| major.checked_add(minor))
+            .ok_or_else(|| format!("Value {input:?} is too large"))?;
+        Ok(if is_negative { -total } else { total })
+    }
+
+    /// Converts the input into the JSON value which should be stored.
+    pub fn to_value(&self, input: &str) -> serde_json::Value {
+        let amount = match self.parse(input) {
+            Ok(amount) => amount,
+            Err(_) => return serde_json::Value::Null,
+        };
+
+        match self.currency.as_ref() {
+            None => serde_json::Value::from(amount),
+            Some(currency) => serde_json::json!({
+                "amount": amount,
+                "currency": currency,
+            }),
+        }
+    }
+
+    /// A sample value which satisfies the constraints.
+    ///
+    /// This is the minimum, or zero if there is no minimum.
+    pub fn example(&self) -> serde_json::Value {
+        let mut amount = self.min.unwrap_or(0);
+        if let Some(max) = self.max {
+            amount = amount.min(max);
+        }
+        self.to_value(&self.format(amount))
+    }
+
+    /// Validates a stored value produced by [MoneyConstraints::to_value].
+    pub fn validate_value(&self, value: &serde_json::Value) -> Result<(), String> {
+        if let Some(currency) = self.currency.as_ref().filter(|_| value.is_object()) {
+            if value.get("currency").and_then(|v| v.as_str()) != Some(currency) {
+                return Err(format!("Value {value} is not in the currency {currency:?}"));
+            }
+        }
+
+        match self.stored_amount(value) {
+            Some(amount) => self.validate_amount(amount),
+            None => Err(format!("Value {value} is not a valid amount")),
+        }
+    }
+
+    /// The number of minor units within a stored value.
+    pub fn stored_amount(&self, value: &serde_json::Value) -> Option<i64> {
+        match self.currency {
+            Some(_) => value.get("amount")?.as_i64(),
+            None => value.as_i64(),
+        }
+    }
+
+    /// Formats a number of minor units for display, i.e. `129999` as `"$1299.99"`.
+    pub fn format(&self, amount: i64) -> String {
+        let places = self.decimal_places as usize;
+        let digits = format!("{:0>width$}", amount.unsigned_abs(), width = places + 1);
+        let (major, minor) = digits.split_at(digits.len() - places);
+
+        let mut formatted = String::new();
+        if amount < 0 {
+            formatted.push('-');
+        }
+        formatted.push_str(self.symbol.as_deref().unwrap_or_default());
+        formatted.push_str(major);
+        if !minor.is_empty() {
+            formatted.push(self.decimal_separator);
+            formatted.push_str(minor);
+        }
+        formatted
+    }
+
+    fn validate_amount(&self, amount: i64) -> Result<(), String> {
+        if let Some(min) = self.min.filter(|min| amount < *min) {
+            return Err(format!(
+                "Value {} cannot be less than {}",
+                self.format(amount),
+                self.format(min)
+            ));
+        }
+
+        if let Some(max) = self.max.filter(|max| amount > *max) {
+            return Err(format!(
+                "Value {} cannot be greater than {}",
+                self.format(amount),
+                self.format(max)
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Validator<String> for MoneyConstraints {
+    type Err = String;
+
+    fn validate(&mut self, input: &String) -> Result<(), Self::Err> {
+        let amount = self.parse(input)?;
+        self.validate_amount(amount)
+    }
+}
+
+fn default_decimal_places() -> u8 {
+    2
+}
+
+fn default_thousands_separator() -> char {
+    ','
+}
+
+fn default_decimal_separator() -> char {
+    '.'
+}
+
+/// Describes a value which falls outside of the range, i.e.
+/// `Value 500 is out of range [1, 100]`.
+///
+/// Exclusive bounds are shown with parentheses rather than brackets.
+fn out_of_range(
+    input: impl Display,
+    min: impl Display,
+    max: impl Display,
+    exclusive_min: bool,
+    exclusive_max: bool,
+) -> String {
+    format!(
+        "Value {input} is out of range {}",
+        range(min, max, exclusive_min, exclusive_max)
+    )
+}
+
+/// A range such as `[1, 100]`, exclusive bounds are shown with parentheses.
+fn range(min: impl Display, max: impl Display, exclusive_min: bool, exclusive_max: bool) -> String {
+    format!(
+        "{}{min}, {max}{}",
+        if exclusive_min { '(' } else { '[' },
+        if exclusive_max { ')' } else { ']' },
+    )
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+/// A no-op validator.
+pub struct BlankValidator;
+impl<T> Validator<T> for BlankValidator {
+    type Err = String;
+
+    fn validate(&mut self, _input: &T) -> Result<(), Self::Err> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn test_float_decimal_places() {
+        let mut constraints = FloatConstraints::<f64> {
+            decimal_places: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(constraints.round(3.14159), 3.14);
+        assert_eq!(constraints.to_value(3.14159), serde_json::json!(3.14));
+
+        constraints.store_as_string = true;
+        assert_eq!(constraints.to_value(3.14159), serde_json::json!("3.14"));
+    }
+
+    #[test]
+    fn test_max_decimal_places() {
+        assert_eq!(
+            check_decimal_places("1.999", Some(2)).unwrap_err(),
+            "Value 1.999 has 3 decimal places, at most 2 are allowed"
+        );
+        assert!(check_decimal_places("1.99", Some(2)).is_ok());
+        assert!(check_decimal_places("1.500", Some(2)).is_ok());
+        assert!(check_decimal_places("1.999", None).is_ok());
+        // This parses to 2.0, so it must be rejected before parsing.
+        assert!(check_decimal_places("1.9999999999999999999", Some(2)).is_err());
+
+        let mut constraints = FloatConstraints::<f64> {
+            max_decimal_places: Some(2),
+            ..Default::default()
+        };
+        assert!(constraints.validate(&1.999).is_err());
+        assert!(constraints.validate(&1.99).is_ok());
+    }
+
+    #[test]
+    fn test_ascii_only() {
+        let mut constraints = StringConstraints {
+            ascii_only: true,
+            ..Default::default()
+        };
+        assert!(constraints.validate(&"cafe-01".to_string()).is_ok());
+        assert_eq!(
+            constraints.validate(&"café".to_string()).unwrap_err(),
+            "Value \"café\" must only contain ASCII characters, found 'é' at position 4"
+        );
+        assert_eq!(
+            constraints
+                .validate(&"deploy🚀now".to_string())
+                .unwrap_err(),
+            "Value \"deploy🚀now\" must only contain ASCII characters, found '🚀' at position 7"
+        );
+    }
+
+    #[test]
+    fn test_length_and_bytes() {
+        let mut constraints = StringConstraints {
+            max_length: 4,
+            max_bytes: Some(6),
+            ..Default::default()
+        };
+        // 3 characters of 2 bytes each.
+        assert!(constraints.validate(&"äöü".to_string()).is_ok());
+        assert_eq!(
+            constraints.validate(&"äöüa".to_string()).unwrap_err(),
+            "Value \"äöüa\" is 7 bytes, which exceeds 6 bytes"
+        );
+        assert_eq!(
+            constraints.validate(&"abcde".to_string()).unwrap_err(),
+            "Value \"abcde\" exceeds the maximum allowed length (4)"
+        );
+
+        let mut constraints = StringConstraints {
+            min_length: 2,
+            min_bytes: Some(4),
+            ..Default::default()
+        };
+        assert!(constraints.validate(&"äö".to_string()).is_ok());
+        assert_eq!(
+            constraints.validate(&"äa".to_string()).unwrap_err(),
+            "Value \"äa\" is 3 bytes, less than the minimum of 4 bytes"
+        );
+        assert_eq!(
+            constraints.validate(&"€".to_string()).unwrap_err(),
+            "Value \"€\" does not meet the minimum required length (2)"
+        );
+    }
+
+    #[test]
+    fn test_rust_identifier() {
+        assert!(Lang::Rust.validate_identifier("my_crate").is_ok());
+        assert!(Lang::Rust.validate_identifier("_private").is_ok());
+        assert_eq!(
+            Lang::Rust.validate_identifier("2fast"),
+            Err("it starts with a digit".to_string())
+        );
+        assert_eq!(
+            Lang::Rust.validate_identifier("my-crate"),
+            Err("'-' is not allowed, only letters, digits and `_` are".to_string())
+        );
+        assert_eq!(
+            Lang::Rust.validate_identifier("async"),
+            Err("it is a reserved word".to_string())
+        );
+        assert_eq!(
+            Lang::Rust.validate_identifier("_"),
+            Err("it is a reserved word".to_string())
+        );
+        // Python keywords are allowed in Rust.
+        assert!(Lang::Rust.validate_identifier("lambda").is_ok());
+    }
+
+    #[test]
+    fn test_python_identifier() {
+        assert!(Lang::Python.validate_identifier("snake_case").is_ok());
+        assert!(Lang::Python.validate_identifier("_").is_ok());
+        assert_eq!(
+            Lang::Python.validate_identifier("lambda"),
+            Err("it is a reserved word".to_string())
+        );
+        assert_eq!(
+            Lang::Python.validate_identifier(""),
+            Err("it is empty".to_string())
+        );
+
+        let mut constraints = StringConstraints {
+            identifier: Some(Lang::Python),
+            ..Default::default()
+        };
+        assert_eq!(
+            constraints.validate(&"9lives".to_string()).unwrap_err(),
+            "Value \"9lives\" is not a valid Python identifier: it starts with a digit"
+        );
+    }
+
+    #[test]
+    fn test_chained_transforms() {
+        let mut constraints = StringConstraints {
+            transforms: vec![
+                Transform::Trim,
+                Transform::Lower,
+                Transform::Replace {
+                    from: " ".to_string(),
+                    to: "-".to_string(),
+                },
+            ],
+            regex: Some("^[a-z-]+$".to_string()),
+            ..Default::default()
+        };
+        let input = "  My Cool App ".to_string();
+        assert_eq!(constraints.transform(&input), "my-cool-app");
+        assert!(constraints.validate(&input).is_ok());
+        assert_eq!(
+            constraints.to_value(input),
+            serde_json::json!("my-cool-app")
+        );
+
+        assert_eq!(Transform::Slug.apply("My Project v2!"), "my-project-v2");
+        assert_eq!(Transform::Upper.apply("eu-west"), "EU-WEST");
+    }
+
+    #[test]
+    fn test_monotonic_order() {
+        let check = |monotonic, values: serde_json::Value| {
+            let constraints = CollectionConstraints {
+                monotonic: Some(monotonic),
+                ..Default::default()
+            };
+            constraints.validate_order(values.as_array().unwrap())
+        };
+
+        assert!(check(Monotonic::Increasing, serde_json::json!([1, 2, 2, 3])).is_ok());
+        assert_eq!(
+            check(Monotonic::Increasing, serde_json::json!([1, 3, 2])).unwrap_err(),
+            "Value 2 is out of order after 3, the values must be increasing"
+        );
+
+        assert!(check(Monotonic::Decreasing, serde_json::json!([3, 2, 2, 1])).is_ok());
+        assert_eq!(
+            check(Monotonic::Decreasing, serde_json::json!([3, 1, 2])).unwrap_err(),
+            "Value 2 is out of order after 1, the values must be decreasing"
+        );
+
+        assert!(check(
+            Monotonic::StrictlyIncreasing,
+            serde_json::json!([0.5, 1, 10])
+        )
+        .is_ok());
+        assert_eq!(
+            check(Monotonic::StrictlyIncreasing, serde_json::json!([1, 5, 5])).unwrap_err(),
+            "Value 5 is out of order after 5, the values must be strictly increasing"
+        );
+        assert_eq!(
+            check(Monotonic::StrictlyIncreasing, serde_json::json!([1, 10, 2])).unwrap_err(),
+            "Value 2 is out of order after 10, the values must be strictly increasing"
+        );
+
+        assert!(check(Monotonic::StrictlyDecreasing, serde_json::json!([3, 2, 1])).is_ok());
+        assert_eq!(
+            check(Monotonic::StrictlyDecreasing, serde_json::json!([3, 2, 2])).unwrap_err(),
+            "Value 2 is out of order after 2, the values must be strictly decreasing"
+        );
+    }
+
+    #[test]
+    fn test_range_hint() {
+        let ints = IntConstraints::<u8> {
+            min: 1,
+            max: 10,
+            show_range: true,
+            ..Default::default()
+        };
+        assert_eq!(ints.range_hint(), Some("[1, 10]".to_string()));
+        let hex = IntConstraints::<u8> {
+            display_radix: Radix::Hex,
+            ..ints
+        };
+        assert_eq!(hex.range_hint(), Some("[0x1, 0xa]".to_string()));
+
+        let ratio = FloatConstraints::<f64> {
+            min: 0.0,
+            max: 100.0,
+            exclusive_min: true,
+            show_range: true,
+            ..Default::default()
+        };
+        assert_eq!(ratio.range_hint(), Some("(0, 100]".to_string()));
+        assert_eq!(
+            ratio.clone().validate(&0.0).unwrap_err(),
+            "Value 0.0 is out of range (0, 100]"
+        );
+
+        let unbounded = FloatConstraints::<f32> {
+            min: 0.5,
+            show_range: true,
+            ..Default::default()
+        };
+        assert_eq!(unbounded.range_hint(), Some("[0.5, ∞)".to_string()));
+        assert_eq!(FloatConstraints::<f32>::default().range_hint(), None);
     }
 }
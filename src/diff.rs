@@ -0,0 +1,143 @@
+use std::mem;
+
+use indexmap::IndexMap;
+
+use crate::{Field, TypeConstraints};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The fields which differ between two versions of a schema, by their
+/// dotted key path.
+pub struct SchemaDiff {
+    /// Fields only in the newer schema.
+    pub added: Vec<String>,
+    /// Fields only in the older schema.
+    pub removed: Vec<String>,
+    /// Fields whose type changed, their answers likely need converting.
+    pub retyped: Vec<String>,
+    /// Fields of the same type whose constraints or settings changed.
+    pub changed: Vec<String>,
+}
+
+impl SchemaDiff {
+    /// Are the schemas the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.retyped.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+/// Compares the fields of both schemas, recursing into nested objects.
+pub(crate) fn diff_fields(
+    old: &IndexMap<String, Field>,
+    new: &IndexMap<String, Field>,
+    path: &str,
+    diff: &mut SchemaDiff,
+) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        diff.removed.push(join(key));
+    }
+
+    for (key, new_field) in new {
+        let path = join(key);
+        let old_field = match old.get(key) {
+            Some(old_field) => old_field,
+            None => {
+                diff.added.push(path);
+                continue;
+            }
+        };
+
+        let (old_type, new_type) = (&old_field.type_constraints, &new_field.type_constraints);
+        if mem::discriminant(old_type) != mem::discriminant(new_type) {
+            diff.retyped.push(path);
+            continue;
+        }
+
+        match (old_type, new_type) {
+            (
+                TypeConstraints::Object { fields: old_fields },
+                TypeConstraints::Object { fields: new_fields },
+            )
+            | (
+                TypeConstraints::ArrayObject {
+                    fields: old_fields, ..
+                },
+                TypeConstraints::ArrayObject {
+                    fields: new_fields, ..
+                },
+            ) => {
+                if without_children(old_field) != without_children(new_field) {
+                    diff.changed.push(path.clone());
+                }
+                diff_fields(old_fields, new_fields, &path, diff);
+            }
+            _ if old_field != new_field => diff.changed.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// The field with its nested fields removed, so only its own settings
+/// are compared.
+fn without_children(field: &Field) -> Field {
+    let mut field = field.clone();
+    match &mut field.type_constraints {
+        TypeConstraints::Object { fields } | TypeConstraints::ArrayObject { fields, .. } => {
+            fields.clear()
+        }
+        _ => {}
+    }
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Schema;
+
+    #[test]
+    fn test_diff_schemas() {
+        let old: Schema = serde_json::from_value(serde_json::json!({"fields": {
+            "name": {"type": "string"},
+            "port": {"type": "u16"},
+            "legacy": {"type": "bool"},
+            "db": {"type": "object", "fields": {
+                "host": {"type": "string"},
+                "user": {"type": "string"},
+            }},
+            "users": {"type": "object[]", "max_items": 3, "fields": {
+                "name": {"type": "string"},
+            }},
+        }}))
+        .unwrap();
+        let new: Schema = serde_json::from_value(serde_json::json!({"fields": {
+            "name": {"type": "string", "min_length": 1},
+            "port": {"type": "string"},
+            "db": {"type": "object", "can_skip": true, "fields": {
+                "host": {"type": "string"},
+                "password": {"type": "string"},
+            }},
+            "users": {"type": "object[]", "max_items": 5, "fields": {
+                "name": {"type": "string"},
+            }},
+            "region": {"type": "string"},
+        }}))
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec!["db.password", "region"]);
+        assert_eq!(diff.removed, vec!["legacy", "db.user"]);
+        assert_eq!(diff.retyped, vec!["port"]);
+        assert_eq!(diff.changed, vec!["db", "name", "users"]);
+        assert!(old.diff(&old).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::{self, Display};
+use std::io;
+use std::time::Duration;
+
+#[derive(Debug)]
+/// An error which can occur while prompting the user.
+pub enum Error {
+    /// The terminal could not be read from or written to.
+    Io(io::Error),
+    /// A field which cannot be skipped was skipped.
+    RequiredFieldSkipped {
+        /// The key of the skipped field.
+        field: String,
+    },
+    /// A required field was left unanswered with [OMIT_INPUT](crate::OMIT_INPUT).
+    FieldOmitted {
+        /// The name of the field being prompted.
+        field: String,
+    },
+    /// The user provided an invalid value too many times.
+    TooManyAttempts {
+        /// The name of the field being prompted.
+        field: String,
+        /// The number of invalid values provided.
+        attempts: usize,
+    },
+    /// The schema for a field is not valid.
+    InvalidSchema {
+        /// The name of the field being prompted.
+        field: String,
+        /// Why the schema is not valid.
+        reason: String,
+    },
+    /// A rule spanning several fields does not hold.
+    ConstraintFailed {
+        /// The message describing the rule.
+        message: String,
+    },
+    /// The answers could not be deserialized into the requested type.
+    Deserialize(serde_json::Error),
+    /// A JSON pointer into a document is not valid.
+    InvalidPointer {
+        /// The JSON pointer.
+        pointer: String,
+        /// Why the pointer is not valid.
+        reason: String,
+    },
+    /// A recorded session could not be read or no longer fits the schema.
+    InvalidRecording {
+        /// Why the recording cannot be replayed.
+        reason: String,
+    },
+    /// A menu cannot be shown because stdin is not a terminal.
+    NotATerminal {
+        /// The name of the field being prompted.
+        field: String,
+    },
+    /// The user declined to start answering the schema.
+    Aborted,
+    /// The user did not answer a prompt in time.
+    Timeout {
+        /// The name of the field being prompted.
+        field: String,
+        /// How long the prompt waited for.
+        timeout: Duration,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{e}"),
+            Error::RequiredFieldSkipped { field } => {
+                write!(f, "Field {field:?} is required and cannot be skipped.")
+            }
+            Error::FieldOmitted { field } => write!(f, "Field {field:?} was omitted."),
+            Error::TooManyAttempts { field, attempts } => write!(
+                f,
+                "Field {field:?} was given an invalid value {attempts} times in a row."
+            ),
+            Error::InvalidSchema { field, reason } => {
+                write!(f, "Field {field:?} has an invalid schema: {reason}")
+            }
+            Error::ConstraintFailed { message } => write!(f, "{message}"),
+            Error::Deserialize(e) => write!(f, "Failed to deserialize the answers: {e}"),
+            Error::InvalidPointer { pointer, reason } => {
+                write!(f, "Invalid pointer {pointer:?}: {reason}")
+            }
+            Error::InvalidRecording { reason } => {
+                write!(f, "The recording cannot be replayed: {reason}")
+            }
+            Error::NotATerminal { field } => write!(
+                f,
+                "Field {field:?} is picked from a menu, which needs stdin to be a terminal."
+            ),
+            Error::Aborted => write!(f, "The prompt was aborted before it started."),
+            Error::Timeout { field, timeout } => write!(
+                f,
+                "Field {field:?} was not answered within {} seconds.",
+                timeout.as_secs_f64()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Deserialize(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
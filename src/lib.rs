@@ -1,23 +1,55 @@
+mod command;
 mod constraints;
+mod diff;
+mod error;
+mod output;
 mod value;
 
 use std::collections::BTreeMap;
-use std::fmt::{Debug, Display};
-use std::io;
+use std::fmt::{self, Debug, Display};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::Duration;
 
-use console::Style;
+use console::{measure_text_width, truncate_str, Key, Style, Term};
 use dialoguer::theme::ColorfulTheme;
-use dialoguer::{Confirm, MultiSelect, Select, Validator};
+use dialoguer::{Confirm, MultiSelect, Select, Sort, Validator};
 use indexmap::IndexMap;
 use inflector::Inflector;
 
+use self::command::run_validate_command;
+use self::constraints::lookup_field;
 pub use self::constraints::{
-    BlankValidator, CollectionConstraints, Conditions, IfCondition, IntConstraints,
-    SelectConstraints, StringConstraints,
+    BlankValidator, BoolConstraints, ByteSizeConstraints, CollectionConstraints, Comparison,
+    Conditions, CrossFieldConstraint, DisabledItem, FloatConstraints, IfCondition, IntConstraints,
+    Lang, MoneyConstraints, Monotonic, Operand, Predicate, Radix, SelectConstraints, ShowIf,
+    StringConstraints, StringWarning, Transform, MAX_MENU_ITEMS, REGEX_SIZE_LIMIT,
+};
+use self::diff::diff_fields;
+pub use self::diff::SchemaDiff;
+pub use self::error::Error;
+#[cfg(feature = "yaml")]
+pub use self::output::to_yaml;
+use self::output::{
+    check_pointer, insert_at_pointer, pointer_tokens, read_recording, redact_fields, redact_value,
+    write_recording,
+};
+pub use self::output::{flatten, merge_answers, unflatten, MergeStrategy, REDACTED_VALUE};
+use self::value::{interact, read_editor, read_inline, ErrorLine};
+pub use self::value::{
+    ByteSize, PromptValue, TraitIntBounds, ValueSettings, NULL_INPUT, OMIT_INPUT,
+    SKIP_REMAINING_INPUT,
 };
-pub use self::value::{PromptValue, TraitIntBounds};
 
 static SKIP_MESSAGE: &str = "Did you mean to skip this field entirely?";
+/// The values of the `Yes`, `No` and `Unknown` items of a tri-state bool.
+static TRI_BOOL_VALUES: [serde_json::Value; 3] = [
+    serde_json::Value::Bool(true),
+    serde_json::Value::Bool(false),
+    serde_json::Value::Null,
+];
 
 #[derive(serde::Deserialize)]
 /// A prompt schema.
@@ -27,20 +59,754 @@ static SKIP_MESSAGE: &str = "Did you mean to skip this field entirely?";
 pub struct Schema {
     /// The schema fields to prompt users.
     pub fields: IndexMap<String, Field>,
+    #[serde(default)]
+    /// Rules spanning several fields, checked once every field is populated.
+    pub constraints: Vec<CrossFieldConstraint>,
+    #[serde(default)]
+    /// The name shown in the banner before the first field.
+    pub title: Option<String>,
+    #[serde(default)]
+    /// The version shown alongside the title.
+    pub version: Option<String>,
+    #[serde(default)]
+    /// Text shown beneath the title before the first field.
+    pub intro: Option<String>,
+    #[serde(default)]
+    /// List the sensitive fields which were answered, masked, once every
+    /// field is populated and ask the user to confirm them.
+    ///
+    /// Declining prompts the top level fields holding them again.
+    pub confirm_secrets: bool,
+    #[serde(default)]
+    /// Ask the user to confirm before the first field is prompted, the
+    /// banner is shown even when quiet so they know what they start.
+    ///
+    /// Declining returns [Error::Aborted].
+    pub require_start_confirmation: bool,
+    #[serde(default)]
+    /// JSON pointers into the answers, i.e. `"/db/host"`, which must hold
+    /// a value other than `null` once every field is populated.
+    ///
+    /// The top level field holding a missing value is prompted again,
+    /// this applies alongside each field's `can_skip`.
+    pub required: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+/// Options controlling how a schema is prompted.
+pub struct PromptOptions {
+    /// Hide the field titles and descriptions.
+    pub quiet: bool,
+    /// How long to wait for the user to answer each prompt before
+    /// [Error::Timeout] is returned, ending the session.
+    ///
+    /// This only applies to interactive reads, supplied answers
+    /// are never subject to a timeout.
+    pub timeout: Option<Duration>,
+    /// Called with the key path and value of each field once it is answered.
+    ///
+    /// Nested fields are called with a dotted path, i.e. `database.host`,
+    /// before the object containing them.
+    pub on_field: Option<FieldCallback>,
+    /// Answers used as the initial value of each field, by key.
+    ///
+    /// Nested fields are looked up within their parent object, fields
+    /// within arrays are not given defaults. A default which does not
+    /// pass the field's constraints is ignored with a warning.
+    pub defaults: BTreeMap<String, serde_json::Value>,
+    /// Descriptions with more lines than this are shown a page at a
+    /// time, waiting for space between pages.
+    ///
+    /// If left blank descriptions are never paged. Boxed descriptions
+    /// are not paged.
+    pub page_descriptions_over: Option<usize>,
+    /// Print the JSON value stored for each field once it is answered,
+    /// with sensitive values redacted.
+    ///
+    /// Nested fields are printed before the value containing them.
+    pub debug_values: bool,
+    /// Show each default beside the prompt, i.e. `Port [8080]`, and accept
+    /// it when the input is left blank rather than filling it in.
+    ///
+    /// Blank input still skips fields which can be skipped.
+    pub empty_accepts_default: bool,
+    /// Can [OMIT_INPUT] be entered to leave a required field unanswered,
+    /// returning [Error::FieldOmitted].
+    ///
+    /// The schema entry points set this themselves, only
+    /// [Schema::prompt_draft] allows it.
+    pub allow_omit: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The answers to a schema which may have unanswered required fields.
+pub struct Draft {
+    /// The answers given, omitted fields are `null`.
+    pub answers: BTreeMap<String, serde_json::Value>,
+    /// The keys of the required fields left unanswered with [OMIT_INPUT].
+    pub omitted: Vec<String>,
+}
+
+#[derive(Clone)]
+/// A callback invoked with the key path and value of an answered field.
+pub struct FieldCallback(Rc<FieldCallbackFn>);
+
+type FieldCallbackFn = dyn Fn(&str, &serde_json::Value);
+
+impl FieldCallback {
+    /// Wraps the callback so it can be set on [PromptOptions].
+    pub fn new(callback: impl Fn(&str, &serde_json::Value) + 'static) -> Self {
+        Self(Rc::new(callback))
+    }
+}
+
+impl Debug for FieldCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FieldCallback")
+    }
 }
 
 impl Schema {
-    pub fn prompt(&self, quiet: bool) -> io::Result<BTreeMap<String, serde_json::Value>> {
+    /// Prompts the user for each field of the schema in turn.
+    ///
+    /// Entering [SKIP_REMAINING_INPUT] in any text prompt skips the current
+    /// field and every field after it. An error is returned if one of the
+    /// skipped fields cannot be skipped.
+    pub fn prompt(&self, quiet: bool) -> Result<BTreeMap<String, serde_json::Value>, Error> {
+        self.prompt_with_options(&PromptOptions {
+            quiet,
+            ..Default::default()
+        })
+    }
+
+    /// Prompts the user for each field of the schema and deserializes
+    /// the answers into the given type.
+    pub fn prompt_into<T: serde::de::DeserializeOwned>(&self, quiet: bool) -> Result<T, Error> {
+        let answers = self.prompt(quiet)?;
+        let object = answers.into_iter().collect::<serde_json::Map<_, _>>();
+        serde_json::from_value(serde_json::Value::Object(object)).map_err(Error::Deserialize)
+    }
+
+    /// Prompts the user for each field of the schema and writes the answers
+    /// into the object at the JSON pointer `at` within the document.
+    ///
+    /// Objects missing along the way are created. The pointer is checked
+    /// before prompting, an error is returned if it traverses a value which
+    /// is not an object.
+    pub fn prompt_into_document(
+        &self,
+        options: &PromptOptions,
+        doc: &mut serde_json::Value,
+        at: &str,
+    ) -> Result<(), Error> {
+        check_pointer(doc, at)?;
+        let answers = self.prompt_with_options(options)?;
+        insert_at_pointer(doc, at, answers)
+    }
+
+    /// Prompts the user for each field of the schema in turn using the given options.
+    pub fn prompt_with_options(
+        &self,
+        options: &PromptOptions,
+    ) -> Result<BTreeMap<String, serde_json::Value>, Error> {
+        self.prompt_fields(options, false)
+            .map(|draft| draft.answers)
+    }
+
+    /// Prompts the user for each field of the schema and records the
+    /// answers to the file, so the session can be replayed with
+    /// [Schema::prompt_from_recording].
+    ///
+    /// The transcript is keyed by the JSON pointer of each value, it is
+    /// written as YAML if the file ends in `.yaml` or `.yml` and the
+    /// `yaml` feature is enabled, otherwise as JSON.
+    pub fn prompt_and_record(
+        &self,
+        options: &PromptOptions,
+        path: impl AsRef<Path>,
+    ) -> Result<BTreeMap<String, serde_json::Value>, Error> {
+        let answers = self.prompt_with_options(options)?;
+        write_recording(&answers, path.as_ref())?;
+        Ok(answers)
+    }
+
+    /// Replays the answers recorded by [Schema::prompt_and_record] without
+    /// prompting the user.
+    ///
+    /// An error is returned if the recorded answers no longer satisfy the
+    /// schema, i.e. a field was added, removed or its constraints changed.
+    pub fn prompt_from_recording(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<BTreeMap<String, serde_json::Value>, Error> {
+        let answers = read_recording(path.as_ref())?;
+        self.validate_answers_with(&answers, true)
+            .map_err(|errors| {
+                let reason = errors
+                    .iter()
+                    .map(|(path, e)| format!("{path}: {e}"))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                Error::InvalidRecording { reason }
+            })?;
+        Ok(answers)
+    }
+
+    /// Prompts the user for each field of the schema, allowing required
+    /// fields to be left unanswered by entering [OMIT_INPUT].
+    ///
+    /// Omitting a field within an object or select condition omits the
+    /// top level field containing it. Within an array entered one item at
+    /// a time it ends the array, like a blank item.
+    pub fn prompt_draft(&self, options: &PromptOptions) -> Result<Draft, Error> {
+        self.prompt_fields(options, true)
+    }
+
+    fn prompt_fields(&self, options: &PromptOptions, allow_omit: bool) -> Result<Draft, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("prompt", fields = self.fields.len()).entered();
+
+        let options = &PromptOptions {
+            allow_omit,
+            ..options.clone()
+        };
+
+        // Styles are already plain when stdout is not a terminal.
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+        }
+
+        if !options.quiet || self.require_start_confirmation {
+            self.print_banner();
+        }
+
+        if self.require_start_confirmation {
+            let proceed = interact("start", options.timeout, || {
+                Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Proceed?")
+                    .interact()
+            })?;
+            if !proceed {
+                return Err(Error::Aborted);
+            }
+        }
+
         let mut populated_fields = BTreeMap::new();
-        for (key, field) in self.fields.iter() {
-            let value = field.prompt(key, quiet, false, &mut populated_fields)?;
-            populated_fields.insert(key.clone(), value);
+        let mut omitted = Vec::new();
+        let mut skip_remaining = false;
+        let mut current_group = None;
+        for (key, field) in self.ordered_fields()? {
+            if !field.is_visible(&populated_fields) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(field = %key, "field hidden by show_if");
+                continue;
+            }
+
+            match field.group.as_ref() {
+                Some(group)
+                    if field.group != current_group && !options.quiet && !skip_remaining =>
+                {
+                    print_heading(group)
+                }
+                _ => {}
+            }
+            current_group = field.group.clone();
+
+            let result = field.prompt(
+                key,
+                options,
+                false,
+                &mut skip_remaining,
+                &mut populated_fields,
+            );
+            let output_key = field.output_key(key);
+            let value = omit_field(result, output_key, allow_omit, &mut omitted)?;
+            if !field.is_section() {
+                populated_fields.insert(output_key.to_string(), value);
+            }
+        }
+
+        self.check_constraints(
+            options,
+            allow_omit,
+            &mut omitted,
+            &mut skip_remaining,
+            &mut populated_fields,
+        )?;
+        self.check_required(
+            options,
+            allow_omit,
+            &mut omitted,
+            &mut skip_remaining,
+            &mut populated_fields,
+        )?;
+        if self.confirm_secrets {
+            self.check_secrets(
+                options,
+                allow_omit,
+                &mut omitted,
+                &mut skip_remaining,
+                &mut populated_fields,
+            )?;
+        }
+        Ok(Draft {
+            answers: populated_fields,
+            omitted,
+        })
+    }
+
+    /// The top level fields ordered so every field follows those it
+    /// depends on, otherwise keeping the declared order.
+    ///
+    /// An error is returned if a dependency is unknown or they form a cycle.
+    fn ordered_fields(&self) -> Result<Vec<(&String, &Field)>, Error> {
+        let mut ordered = Vec::with_capacity(self.fields.len());
+        let mut visiting = Vec::new();
+        for key in self.fields.keys() {
+            self.visit_field(key, &mut visiting, &mut ordered)?;
+        }
+        Ok(ordered)
+    }
+
+    fn visit_field<'a>(
+        &'a self,
+        key: &str,
+        visiting: &mut Vec<&'a str>,
+        ordered: &mut Vec<(&'a String, &'a Field)>,
+    ) -> Result<(), Error> {
+        let (key, field) = match self.fields.get_key_value(key) {
+            Some(entry) => entry,
+            None => {
+                return Err(Error::InvalidSchema {
+                    field: visiting.last().unwrap_or(&key).to_string(),
+                    reason: format!("depends_on refers to an unknown field {key:?}"),
+                })
+            }
+        };
+        if ordered.iter().any(|(ordered_key, _)| *ordered_key == key) {
+            return Ok(());
+        }
+
+        if let Some(start) = visiting.iter().position(|visited| *visited == key) {
+            let cycle = visiting[start..]
+                .iter()
+                .chain([&key.as_str()])
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(Error::InvalidSchema {
+                field: key.clone(),
+                reason: format!("depends_on forms a cycle: {cycle}"),
+            });
+        }
+
+        visiting.push(key);
+        for dependency in field.depends_on.iter() {
+            self.visit_field(dependency, visiting, ordered)?;
+        }
+        visiting.pop();
+        ordered.push((key, field));
+        Ok(())
+    }
+
+    /// Prints the title, version and intro, if any are set.
+    fn print_banner(&self) {
+        let title = match (self.title.as_ref(), self.version.as_ref()) {
+            (Some(title), Some(version)) => Some(format!("{title} v{version}")),
+            (Some(title), None) => Some(title.clone()),
+            (None, Some(version)) => Some(format!("v{version}")),
+            (None, None) => None,
+        };
+        if let Some(title) = title {
+            let styled = Style::new().bold().cyan().for_stdout().apply_to(title);
+            println!("{styled}");
+        }
+
+        let styled = Style::new().italic().for_stdout();
+        for line in self.intro.iter().flat_map(|intro| intro.lines()) {
+            println!("{}", styled.apply_to(line));
+        }
+    }
+
+    /// Checks the cross field constraints, prompting the offending
+    /// fields again until every rule holds.
+    fn check_constraints(
+        &self,
+        options: &PromptOptions,
+        allow_omit: bool,
+        omitted: &mut Vec<String>,
+        skip_remaining: &mut bool,
+        populated_fields: &mut BTreeMap<String, serde_json::Value>,
+    ) -> Result<(), Error> {
+        let error_style = Style::new().red().italic().for_stdout();
+        while let Some((constraint, message)) = self
+            .constraints
+            .iter()
+            .find_map(|constraint| Some((constraint, constraint.check(populated_fields).err()?)))
+        {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(rule = %constraint.rule, %message, "cross field constraint failed");
+
+            // Skipped fields cannot be prompted again.
+            if *skip_remaining {
+                return Err(Error::ConstraintFailed { message });
+            }
+
+            println!("{}", error_style.apply_to(&message));
+            let mut reprompted = false;
+            for output_key in constraint.fields_to_reprompt() {
+                let (key, field) = match self
+                    .fields
+                    .iter()
+                    .find(|(key, field)| field.output_key(key) == output_key)
+                {
+                    Some((key, field))
+                        if field.is_visible(populated_fields) && !field.immutable =>
+                    {
+                        (key, field)
+                    }
+                    _ => continue,
+                };
+
+                omitted.retain(|omitted_key| omitted_key != &output_key);
+                let result = field.prompt(key, options, false, skip_remaining, populated_fields);
+                let value = omit_field(result, &output_key, allow_omit, omitted)?;
+                populated_fields.insert(output_key, value);
+                reprompted = true;
+            }
+
+            if !reprompted {
+                return Err(Error::ConstraintFailed { message });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the required pointers hold a value, prompting the top level
+    /// field holding a missing value again until every one does.
+    fn check_required(
+        &self,
+        options: &PromptOptions,
+        allow_omit: bool,
+        omitted: &mut Vec<String>,
+        skip_remaining: &mut bool,
+        populated_fields: &mut BTreeMap<String, serde_json::Value>,
+    ) -> Result<(), Error> {
+        let error_style = Style::new().red().italic().for_stdout();
+        while let Some(pointer) = self.missing_required(populated_fields) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(pointer, "required value is missing");
+
+            let required = || Error::RequiredFieldSkipped {
+                field: pointer.to_string(),
+            };
+            // Skipped fields cannot be prompted again.
+            if *skip_remaining {
+                return Err(required());
+            }
+
+            let output_key = match pointer_tokens(pointer)?.into_iter().next() {
+                Some(output_key) => output_key,
+                None => return Err(required()),
+            };
+            let (key, field) = match self
+                .fields
+                .iter()
+                .find(|(key, field)| field.output_key(key) == output_key)
+            {
+                Some((key, field)) if field.is_visible(populated_fields) && !field.immutable => {
+                    (key, field)
+                }
+                _ => return Err(required()),
+            };
+
+            let message = format!("A value is required for {pointer:?}.");
+            println!("{}", error_style.apply_to(message));
+            omitted.retain(|omitted_key| omitted_key != &output_key);
+            let result = field.prompt(key, options, false, skip_remaining, populated_fields);
+            let value = omit_field(result, &output_key, allow_omit, omitted)?;
+            populated_fields.insert(output_key, value);
+        }
+        Ok(())
+    }
+
+    /// The first required pointer which does not hold a value.
+    fn missing_required(&self, answers: &BTreeMap<String, serde_json::Value>) -> Option<&str> {
+        let root = serde_json::Value::Object(
+            answers
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        );
+        self.required
+            .iter()
+            .find(|pointer| root.pointer(pointer).is_none_or(|value| value.is_null()))
+            .map(String::as_str)
+    }
+
+    /// Lists the answered sensitive fields, prompting the fields holding
+    /// them again until the user confirms they are as expected.
+    fn check_secrets(
+        &self,
+        options: &PromptOptions,
+        allow_omit: bool,
+        omitted: &mut Vec<String>,
+        skip_remaining: &mut bool,
+        populated_fields: &mut BTreeMap<String, serde_json::Value>,
+    ) -> Result<(), Error> {
+        // Skipped fields cannot be prompted again.
+        while !*skip_remaining {
+            let secrets = secret_paths(&self.redact(populated_fields));
+            if secrets.is_empty() {
+                return Ok(());
+            }
+
+            let heading = match secrets.len() {
+                1 => "1 secret was collected:".to_string(),
+                count => format!("{count} secrets were collected:"),
+            };
+            let styled = Style::new().bold().for_stdout();
+            println!("\n{}", styled.apply_to(heading));
+            for (_, secret) in secrets.iter() {
+                println!("  {secret}: {REDACTED_VALUE}");
+            }
+
+            let confirmed = interact("secrets", options.timeout, || {
+                Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Are these the secrets you expected?")
+                    .default(true)
+                    .interact()
+            })?;
+            if confirmed {
+                return Ok(());
+            }
+
+            let mut output_keys = secrets
+                .iter()
+                .map(|(output_key, _)| output_key.as_str())
+                .collect::<Vec<_>>();
+            output_keys.dedup();
+            let mut prompted = false;
+            for output_key in output_keys {
+                let (key, field) = match self
+                    .fields
+                    .iter()
+                    .find(|(key, field)| field.output_key(key) == output_key)
+                {
+                    Some((key, field))
+                        if field.is_visible(populated_fields) && !field.immutable =>
+                    {
+                        (key, field)
+                    }
+                    _ => continue,
+                };
+
+                omitted.retain(|omitted_key| omitted_key != output_key);
+                let result = field.prompt(key, options, false, skip_remaining, populated_fields);
+                let value = omit_field(result, output_key, allow_omit, omitted)?;
+                populated_fields.insert(output_key.to_string(), value);
+                prompted = true;
+            }
+
+            // Secrets held by fields inserted at the root by a condition
+            // have no top level field to prompt again.
+            if !prompted {
+                let error_style = Style::new().red().italic().for_stdout();
+                println!(
+                    "{}",
+                    error_style.apply_to("These secrets cannot be entered again.")
+                );
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the answers with the values of sensitive fields
+    /// replaced by [REDACTED_VALUE], for use in any summary of the answers.
+    pub fn redact(
+        &self,
+        answers: &BTreeMap<String, serde_json::Value>,
+    ) -> BTreeMap<String, serde_json::Value> {
+        let mut root = answers
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<serde_json::Map<_, _>>();
+
+        let mut root_fields = vec![&self.fields];
+        while let Some(fields) = root_fields.pop() {
+            redact_fields(fields, &mut root, &mut root_fields);
+        }
+
+        root.into_iter().collect()
+    }
+
+    /// Checks the schema itself is usable, without prompting the user.
+    ///
+    /// Every problem is collected and returned together as pairs of the
+    /// field's key path and the error message. Regexes are compiled with
+    /// a size limit of [REGEX_SIZE_LIMIT] bytes so overly complex patterns
+    /// are reported here rather than when a value is validated.
+    pub fn validate(&self) -> Result<(), Vec<(String, String)>> {
+        let mut errors = Vec::new();
+        match self.ordered_fields() {
+            Ok(ordered) => {
+                let mut answered = Vec::new();
+                for (key, field) in ordered {
+                    let mut copies = Vec::new();
+                    collect_copy_from(key, field, &mut copies);
+                    for (path, source) in copies {
+                        let root = source.split('.').next().unwrap_or(source);
+                        if !answered.contains(&root) {
+                            let reason = format!("copy_from {source:?} is not answered before it");
+                            errors.push((path, reason));
+                        }
+                    }
+
+                    answered.push(field.output_key(key));
+                    if let TypeConstraints::Select { conditions, .. } = &field.type_constraints {
+                        let root_fields = conditions
+                            .all_fields()
+                            .filter(|_| conditions.insert_at_root)
+                            .flat_map(|fields| fields.iter());
+                        answered.extend(root_fields.map(|(key, field)| field.output_key(key)));
+                    }
+                }
+            }
+            Err(Error::InvalidSchema { field, reason }) => errors.push((field, reason)),
+            Err(_) => {}
+        }
+        collect_schema_errors(&self.fields, "", &mut errors);
+        for pointer in self
+            .required
+            .iter()
+            .filter(|pointer| !pointer.starts_with('/'))
+        {
+            let reason = "A required pointer must start with '/'".to_string();
+            errors.push((pointer.clone(), reason));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Reports likely mistakes in the schema which do not stop it being
+    /// used, as pairs of the field's key path and the warning message.
+    ///
+    /// A string field which cannot be skipped is warned about if its
+    /// constraints accept an empty string, i.e. a regex of `.*` with no
+    /// minimum length. Such fields are left as they are, add a
+    /// `min_length` of 1 to require a non-empty value.
+    pub fn lint(&self) -> Vec<(String, String)> {
+        let mut warnings = Vec::new();
+        collect_schema_warnings(&self.fields, "", &mut warnings);
+        warnings
+    }
+
+    /// Compares the fields of this schema with a newer version of it.
+    ///
+    /// Fields are matched by their key path, so a renamed field is both
+    /// removed and added. Nested objects are compared field by field.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        let mut diff = SchemaDiff::default();
+        diff_fields(&self.fields, &other.fields, "", &mut diff);
+        diff
+    }
+
+    /// Validates a supplied set of answers against the schema without
+    /// prompting the user.
+    ///
+    /// Every constraint failure is collected and returned together as
+    /// pairs of the field's key path and the error message. Keys which
+    /// are not in the schema are ignored.
+    pub fn validate_answers(
+        &self,
+        answers: &BTreeMap<String, serde_json::Value>,
+    ) -> Result<(), Vec<(String, String)>> {
+        self.validate_answers_with(answers, false)
+    }
+
+    /// Validates a supplied set of answers against the schema without
+    /// prompting the user, like [Schema::validate_answers].
+    ///
+    /// If `strict` is set every key, including those within nested
+    /// objects, which is not in the schema is reported as an error too.
+    pub fn validate_answers_with(
+        &self,
+        answers: &BTreeMap<String, serde_json::Value>,
+        strict: bool,
+    ) -> Result<(), Vec<(String, String)>> {
+        let root = answers
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<serde_json::Map<_, _>>();
+
+        let mut errors = Vec::new();
+        if strict {
+            let mut root_fields = vec![&self.fields];
+            collect_root_fields(&self.fields, &mut root_fields);
+            collect_unknown_keys(&root_fields, None, &root, "", &mut errors);
+        }
+        collect_errors(&self.fields, &root, answers, "", &mut errors);
+
+        for constraint in self.constraints.iter() {
+            if let Err(e) = constraint.check(answers) {
+                let path = constraint.fields_to_reprompt().join(", ");
+                errors.push((path, e));
+            }
+        }
+
+        let root = serde_json::Value::Object(root);
+        for pointer in self.required.iter() {
+            if root.pointer(pointer).is_none_or(|value| value.is_null()) {
+                errors.push((pointer.clone(), "A value is required".to_string()));
+            }
         }
-        Ok(populated_fields)
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Produces a sample set of answers which satisfies the schema,
+    /// without prompting the user.
+    ///
+    /// Each field takes its smallest allowed value, the first select item
+    /// or the minimum number of array items. Array items count up from the
+    /// smallest value so they are distinct by `unique_by` and follow a
+    /// `monotonic` order. Fields are answered in `depends_on` order.
+    ///
+    /// The result is best effort and may not pass [Schema::validate_answers]:
+    /// - strings matching a regex are tried with a few simple candidates
+    ///   before giving up,
+    /// - distinct items can run out in a narrow range of values,
+    /// - cross field `constraints` are not considered,
+    /// - a `required` pointer to a hidden field, or to a select without
+    ///   items, is left empty.
+    pub fn example(&self) -> serde_json::Value {
+        let fields = self
+            .ordered_fields()
+            .unwrap_or_else(|_| self.fields.iter().collect());
+        let mut answers = BTreeMap::new();
+        for (key, field) in fields {
+            if field.is_section() || !field.is_visible(&answers) {
+                continue;
+            }
+
+            let value = field.type_constraints.example(&answers);
+            answers.insert(field.output_key(key).to_string(), value);
+        }
+        serde_json::Value::Object(answers.into_iter().collect())
     }
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Clone, PartialEq)]
 pub struct Field {
     #[serde(default)]
     /// The display name to show as the prompt rather
@@ -50,64 +816,508 @@ pub struct Field {
     /// An optional prompt message.
     ///
     /// If left blank this defaults to the display name or field name.
+    ///
+    /// For array types, `{index}` is replaced with the 1-based index
+    /// of the element being entered, i.e. `"Hostname #{index}"`.
     pub prompt: Option<String>,
     #[serde(default)]
     /// The help description to display if enabled.
     pub description: String,
+    #[serde(default)]
+    /// Should the description be rendered inside a bordered box.
+    pub boxed_description: bool,
     #[serde(flatten)]
     /// The specific type and relevant constraints for the field.
     pub type_constraints: TypeConstraints,
     #[serde(default)]
     /// Can the value be skipped/left blank.
     pub can_skip: bool,
+    #[serde(default)]
+    /// The maximum number of invalid values the user can enter in a row
+    /// before [Error::TooManyAttempts] is returned.
+    ///
+    /// If left blank the user is re-prompted until a valid value is given.
+    pub max_attempts: Option<usize>,
+    #[serde(default)]
+    /// Only prompt the field if another field has the given value.
+    ///
+    /// Hidden fields are omitted from the output entirely.
+    pub show_if: Option<ShowIf>,
+    #[serde(default)]
+    /// The heading of the group the field is shown under.
+    ///
+    /// The heading is printed once for consecutive top level fields in the
+    /// same group, a group which is split by other fields is printed again.
+    /// Groups do not change the shape of the output.
+    pub group: Option<String>,
+    #[serde(default)]
+    /// A shell command which is given the value on stdin after the
+    /// built-in constraints pass, a non-zero exit rejects the value.
+    ///
+    /// Strings are passed as is, any other value as JSON. The command only
+    /// runs for values entered at a prompt, not for defaults, recordings
+    /// or answers being validated. This requires the `validate-command`
+    /// feature.
+    pub validate_command: Option<String>,
+    #[serde(default)]
+    /// Should the value be hidden when the answers are shown back to the user.
+    ///
+    /// The real value is still returned, see [Schema::redact]. Every value
+    /// within a sensitive object or array is hidden.
+    pub sensitive: bool,
+    #[serde(default)]
+    /// Text shown before the prompt, i.e. an emoji or tag.
+    pub prefix: Option<String>,
+    #[serde(default)]
+    /// Should the value be entered twice, re-prompting both entries
+    /// until they match.
+    ///
+    /// This only applies to bool, string, number, byte size and money fields.
+    pub confirm: bool,
+    #[serde(default)]
+    /// The key the answer is stored under rather than the field key.
+    ///
+    /// The field key is still used for the prompt, defaults and error
+    /// paths, `show_if` and constraints refer to the output key.
+    pub output_key: Option<String>,
+    #[serde(default)]
+    /// Can [NULL_INPUT] be entered to store `null`, even if the field
+    /// cannot be skipped.
+    ///
+    /// This only applies to fields entered as text, array items cannot be
+    /// `null`. The sentinel is checked before the value is parsed so it
+    /// is never stored as a string.
+    pub allow_explicit_null: bool,
+    #[serde(default)]
+    /// A template shown once the field is answered, i.e.
+    /// `"https://{host}:{value}/"`.
+    ///
+    /// `{value}` is replaced by the field's value and `{path}` by the value
+    /// of the field at the dotted path among those answered so far.
+    /// Unknown placeholders are left as is.
+    pub preview: Option<String>,
+    #[serde(default)]
+    /// The keys of the top level fields which must be prompted first.
+    ///
+    /// Fields are otherwise prompted in the order they are declared.
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    /// The initial value of the field, unless the prompt options give one.
+    ///
+    /// This is separate from a select's `default`, which picks an item.
+    ///
+    /// Within a string default `{path}` is replaced by the value of the
+    /// field at the dotted path among those answered so far. For the
+    /// fields of a select's condition `{selected}` is replaced by the
+    /// selected item, `null` for the fields prompted on skip. The rendered
+    /// text is parsed as JSON for fields which are not strings.
+    pub default_answer: Option<serde_json::Value>,
+    #[serde(default)]
+    /// The dotted path of an earlier field whose value can be copied,
+    /// i.e. `shipping_address`.
+    ///
+    /// Once that field is answered the user is asked if this field is the
+    /// same rather than being prompted. The copied value must still pass
+    /// this field's constraints.
+    pub copy_from: Option<String>,
+    #[serde(default)]
+    /// Should the field keep its first answer when it is prompted again,
+    /// i.e. by a condition inserting fields at the root.
+    ///
+    /// Immutable fields are not prompted again to satisfy cross field
+    /// constraints, a skipped answer is kept too.
+    pub immutable: bool,
 }
 
 impl Field {
+    /// Prompts the user for the field's value.
+    ///
+    /// The path is the dotted key of the field within the answers.
     pub fn prompt(
         &self,
-        field_key: &str,
-        quiet: bool,
+        path: &str,
+        options: &PromptOptions,
         hide_title: bool,
+        skip_remaining: &mut bool,
         populated_fields: &mut BTreeMap<String, serde_json::Value>,
-    ) -> io::Result<serde_json::Value> {
-        if !quiet {
-            if !hide_title && self.display_name.is_some() {
-                let styled = Style::new()
-                    .bold()
-                    .underlined()
-                    .for_stdout()
-                    .apply_to(self.display_name.as_ref().unwrap());
-                println!("\n{styled}:");
-            } else if !hide_title {
-                println!();
+    ) -> Result<serde_json::Value, Error> {
+        self.prompt_with_selected(
+            path,
+            options,
+            hide_title,
+            None,
+            skip_remaining,
+            populated_fields,
+        )
+    }
+
+    /// Prompts the user for the field's value, given the item selected
+    /// to trigger the condition the field is part of.
+    fn prompt_with_selected(
+        &self,
+        path: &str,
+        options: &PromptOptions,
+        hide_title: bool,
+        selected: Option<&serde_json::Value>,
+        skip_remaining: &mut bool,
+        populated_fields: &mut BTreeMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("field", path).entered();
+
+        if self.immutable {
+            let output_path = match path.rsplit_once('.') {
+                Some((parent, key)) => format!("{parent}.{}", self.output_key(key)),
+                None => self.output_key(path).to_string(),
+            };
+            if let Some(existing) = lookup_field(populated_fields, &output_path) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("immutable field already answered");
+                return Ok(existing.clone());
+            }
+        }
+
+        let value = self.prompt_value(
+            path,
+            options,
+            hide_title,
+            selected,
+            skip_remaining,
+            populated_fields,
+        )?;
+
+        #[cfg(feature = "tracing")]
+        if !self.is_section() {
+            let mut value = value.clone();
+            redact_value(self, &mut value, &mut Vec::new());
+            tracing::debug!(%value, "field answered");
+        }
+
+        if options.debug_values && !self.is_section() {
+            let mut value = value.clone();
+            redact_value(self, &mut value, &mut Vec::new());
+            let styled = Style::new().dim().for_stdout();
+            println!("{}", styled.apply_to(format!("{path} = {value}")));
+        }
+
+        if let Some(on_field) = options.on_field.as_ref().filter(|_| !self.is_section()) {
+            (on_field.0)(path, &value);
+        }
+
+        Ok(value)
+    }
+
+    fn prompt_value(
+        &self,
+        path: &str,
+        options: &PromptOptions,
+        hide_title: bool,
+        selected: Option<&serde_json::Value>,
+        skip_remaining: &mut bool,
+        populated_fields: &mut BTreeMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, Error> {
+        if let TypeConstraints::Section { title } = &self.type_constraints {
+            if !options.quiet && !*skip_remaining {
+                self.print_section(title, options)?;
             }
+            return Ok(serde_json::Value::Null);
+        }
+
+        if *skip_remaining {
+            return self.skipped_value(path);
+        }
 
-            let styled = Style::new().dim().italic().for_stdout();
-            for line in self.description.lines() {
-                println!("  {}", styled.apply_to(line));
+        if !options.quiet && !hide_title {
+            match self.display_name.as_ref() {
+                Some(display_name) => {
+                    let styled = Style::new()
+                        .bold()
+                        .underlined()
+                        .for_stdout()
+                        .apply_to(display_name);
+                    println!("\n{styled}:");
+                }
+                None => println!(),
             }
         }
 
+        if !options.quiet && self.boxed_description {
+            print_boxed(&self.description);
+        } else if !options.quiet {
+            print_description(&self.description, options.page_descriptions_over)?;
+        }
+
         let field_name = self
             .prompt
             .as_deref()
             .or(self.display_name.as_deref())
             .map(str::to_string)
-            .unwrap_or_else(|| field_key.to_title_case());
-        self.type_constraints
-            .prompt(&field_name, self.can_skip, quiet, populated_fields)
-    }
-}
+            .unwrap_or_else(|| path.rsplit('.').next().unwrap_or(path).to_title_case());
+        let field_name = match self.prefix.as_ref() {
+            Some(prefix) => format!("{prefix} {field_name}"),
+            None => field_name,
+        };
 
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "lowercase")]
-#[serde(tag = "type")]
-pub enum TypeConstraints {
-    /// A boolean type.
-    Bool,
-    /// A string type.
-    String(StringConstraints),
-    /// A u64 type.
+        if let Some(copied) = self.copied_value(&field_name, options, populated_fields)? {
+            return Ok(copied);
+        }
+
+        let settings = ValueSettings {
+            can_skip: self.can_skip,
+            max_attempts: self.max_attempts,
+            timeout: options.timeout,
+            default: self.default_value(path, options, selected, populated_fields),
+            allow_null: self.allow_explicit_null,
+            empty_accepts_default: options.empty_accepts_default,
+            max_decimal_places: None,
+            allow_omit: options.allow_omit,
+        };
+        let value = self.read_accepted(&field_name, path, &settings, |field_name, settings| {
+            let value = self.type_constraints.prompt(
+                field_name,
+                path,
+                settings,
+                options,
+                skip_remaining,
+                populated_fields,
+            )?;
+            Ok((value, *skip_remaining))
+        })?;
+
+        if *skip_remaining && value.is_null() {
+            return self.skipped_value(path);
+        }
+
+        let warning_style = Style::new().yellow().italic().for_stdout();
+        for warning in self.type_constraints.warnings(&value) {
+            println!("{}", warning_style.apply_to(warning));
+        }
+
+        match self.preview.as_ref() {
+            Some(preview) if !options.quiet && !value.is_null() => {
+                let shown = match self.sensitive {
+                    true => serde_json::Value::from(REDACTED_VALUE),
+                    false => value.clone(),
+                };
+                let preview = interpolate(preview, populated_fields, &[("value", &shown)]);
+                let styled = Style::new()
+                    .dim()
+                    .for_stdout()
+                    .apply_to(format!("→ {preview}"));
+                println!("  {styled}");
+            }
+            _ => {}
+        }
+
+        Ok(value)
+    }
+
+    /// The key the field's answer is stored under, given its field key.
+    pub fn output_key<'a>(&'a self, key: &'a str) -> &'a str {
+        self.output_key.as_deref().unwrap_or(key)
+    }
+
+    /// Is the field a section heading rather than a value.
+    ///
+    /// Sections contribute nothing to the output.
+    pub fn is_section(&self) -> bool {
+        matches!(self.type_constraints, TypeConstraints::Section { .. })
+    }
+
+    /// Is the field shown given the values populated so far.
+    pub fn is_visible(&self, populated_fields: &BTreeMap<String, serde_json::Value>) -> bool {
+        match self.show_if.as_ref() {
+            None => true,
+            Some(rule) => rule.matches(populated_fields),
+        }
+    }
+
+    /// Validates an already parsed value against the field's constraints
+    /// without prompting the user.
+    ///
+    /// A `null` value is accepted if the field can be skipped.
+    pub fn validate_value(&self, value: &serde_json::Value) -> Result<(), String> {
+        if value.is_null() && (self.can_skip || self.allow_explicit_null) {
+            return Ok(());
+        }
+
+        self.type_constraints.validate_value(value)
+    }
+
+    /// The default value for the field, if it passes the field's constraints.
+    fn default_value(
+        &self,
+        path: &str,
+        options: &PromptOptions,
+        selected: Option<&serde_json::Value>,
+        populated_fields: &BTreeMap<String, serde_json::Value>,
+    ) -> Option<serde_json::Value> {
+        let default = match lookup_field(&options.defaults, path).filter(|v| !v.is_null()) {
+            Some(default) => default.clone(),
+            None => match self.default_answer.as_ref()? {
+                serde_json::Value::String(template) => {
+                    let placeholders = selected
+                        .map(|selected| ("selected", selected))
+                        .into_iter()
+                        .collect::<Vec<_>>();
+                    let rendered = interpolate(template, populated_fields, &placeholders);
+                    match &self.type_constraints {
+                        TypeConstraints::String(_) | TypeConstraints::Money(_) => {
+                            serde_json::Value::from(rendered)
+                        }
+                        _ => serde_json::from_str(&rendered)
+                            .unwrap_or(serde_json::Value::from(rendered)),
+                    }
+                }
+                default => default.clone(),
+            },
+        };
+        match self.validate_value(&default) {
+            Ok(()) => Some(default),
+            Err(e) => {
+                let warning_style = Style::new().yellow().italic().for_stdout();
+                println!(
+                    "{}",
+                    warning_style.apply_to(format!("Ignoring the default for {path:?}: {e}"))
+                );
+                None
+            }
+        }
+    }
+
+    /// Reads values until one is accepted, asking for it a second time if
+    /// `confirm` is set and running the `validate_command`.
+    ///
+    /// `read` prompts for a value with the given field name and settings,
+    /// returning it along with whether the remaining fields were skipped.
+    fn read_accepted(
+        &self,
+        field_name: &str,
+        path: &str,
+        settings: &ValueSettings,
+        mut read: impl FnMut(&str, ValueSettings) -> Result<(serde_json::Value, bool), Error>,
+    ) -> Result<serde_json::Value, Error> {
+        let mut failed_attempts = 0;
+        loop {
+            let (value, _) = read(field_name, settings.clone())?;
+
+            let confirmed = if self.confirm && self.type_constraints.is_scalar() && !value.is_null()
+            {
+                let confirm_settings = ValueSettings {
+                    default: None,
+                    ..settings.clone()
+                };
+                let (confirmation, skipped) =
+                    read(&format!("Confirm {field_name}"), confirm_settings)?;
+                skipped || confirmation == value
+            } else {
+                true
+            };
+
+            let verdict = match self.validate_command.as_ref() {
+                _ if !confirmed => Err("The values do not match.".to_string()),
+                Some(command) if !value.is_null() => run_validate_command(command, &value)
+                    .map_err(|reason| Error::InvalidSchema {
+                        field: path.to_string(),
+                        reason,
+                    })?,
+                _ => Ok(()),
+            };
+            match verdict {
+                Ok(()) => return Ok(value),
+                Err(e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(
+                        error = if self.sensitive {
+                            REDACTED_VALUE
+                        } else {
+                            e.as_str()
+                        },
+                        "value rejected"
+                    );
+
+                    let error_style = Style::new().red().italic().for_stdout();
+                    println!("{}", error_style.apply_to(e));
+
+                    failed_attempts += 1;
+                    if self.max_attempts.is_some_and(|max| failed_attempts >= max) {
+                        return Err(Error::TooManyAttempts {
+                            field: field_name.to_string(),
+                            attempts: failed_attempts,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// The value of the `copy_from` field, if it is answered and the
+    /// user chooses to copy it.
+    fn copied_value(
+        &self,
+        field_name: &str,
+        options: &PromptOptions,
+        populated_fields: &BTreeMap<String, serde_json::Value>,
+    ) -> Result<Option<serde_json::Value>, Error> {
+        let (source, copied) = match self.copy_from.as_ref() {
+            Some(source) => match lookup_field(populated_fields, source) {
+                Some(copied) if !copied.is_null() => (source, copied),
+                _ => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+
+        let prompt = format!("{field_name}: same as {source}?");
+        let copy = interact(field_name, options.timeout, move || {
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .default(true)
+                .interact()
+        })?;
+        if !copy {
+            return Ok(None);
+        }
+
+        match self.validate_value(copied) {
+            Ok(()) => Ok(Some(copied.clone())),
+            Err(e) => {
+                let error_style = Style::new().red().italic().for_stdout();
+                println!(
+                    "{}",
+                    error_style.apply_to(format!("The value cannot be copied: {e}"))
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Prints the section heading followed by its description.
+    fn print_section(&self, title: &str, options: &PromptOptions) -> io::Result<()> {
+        print_heading(title);
+        print_description(&self.description, options.page_descriptions_over)
+    }
+
+    /// The value of the field when it is skipped by the user.
+    fn skipped_value(&self, path: &str) -> Result<serde_json::Value, Error> {
+        if self.can_skip {
+            Ok(serde_json::Value::Null)
+        } else {
+            Err(Error::RequiredFieldSkipped {
+                field: path.to_string(),
+            })
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[serde(tag = "type")]
+pub enum TypeConstraints {
+    /// A boolean type.
+    Bool(BoolConstraints),
+    /// A string type.
+    String(StringConstraints),
+    /// A u64 type.
     U64(IntConstraints<u64>),
     /// A u32 type.
     U32(IntConstraints<u32>),
@@ -124,9 +1334,27 @@ pub enum TypeConstraints {
     /// A i8 type.
     I8(IntConstraints<i8>),
     /// A f64 type.
-    F64(IntConstraints<f64>),
+    F64(FloatConstraints<f64>),
     /// A f32 type.
-    F32(IntConstraints<f32>),
+    F32(FloatConstraints<f32>),
+    /// A size in bytes entered with an optional unit, i.e. `10MB` or `1.5GiB`.
+    ///
+    /// Units are case sensitive so bits such as `Mb` are not mistaken for
+    /// bytes, and the size must be a whole number of bytes. The value is
+    /// stored as the total number of bytes.
+    ByteSize(ByteSizeConstraints),
+    /// An amount of money entered with an optional symbol and separators,
+    /// i.e. `$1,299.99`.
+    ///
+    /// The value is stored as the number of minor units, i.e. cents.
+    Money(MoneyConstraints),
+    /// A section heading shown between fields.
+    ///
+    /// Sections are not prompted and contribute nothing to the output.
+    Section {
+        /// The heading to display.
+        title: String,
+    },
     /// A select menu
     Select {
         #[serde(flatten)]
@@ -134,11 +1362,52 @@ pub enum TypeConstraints {
         #[serde(rename = "then", default)]
         conditions: Conditions,
     },
+    /// A group of boolean flags where exactly one is true.
+    ///
+    /// The value is an object of each option to whether it is set, or
+    /// the name of the set option if `emit_name` is set.
+    OneOfBool {
+        /// The names of the flags.
+        options: Vec<String>,
+        #[serde(default)]
+        /// Store the name of the set option rather than every flag.
+        emit_name: bool,
+    },
+    /// A boolean which can also be left unknown, picked from a menu of
+    /// `Yes`, `No` and `Unknown`.
+    ///
+    /// The value is `true`, `false` or `null` for unknown. Escaping the
+    /// menu of a field which can be skipped also stores `null`, so a
+    /// skipped field reads as unknown. Like other menus, a timeout ends
+    /// the session rather than picking the default.
+    TriBool,
+    /// A set of named flags chosen from a checkbox list.
+    ///
+    /// The value is an object of each option to whether it was checked.
+    /// Escaping the list skips the field, storing `null`.
+    Flags {
+        /// The names of the flags.
+        options: Vec<String>,
+    },
     /// A nested object.
     Object {
         /// The fields within the nested object.
         fields: IndexMap<String, Field>,
     },
+    #[serde(rename = "object[]")]
+    /// An array of nested objects.
+    ArrayObject {
+        #[serde(flatten)]
+        constraints: CollectionConstraints,
+        /// The fields within each nested object.
+        fields: IndexMap<String, Field>,
+        #[serde(default)]
+        /// The keys whose values must not be shared by two items, i.e.
+        /// `["username"]`.
+        ///
+        /// Items where every one of the keys is `null` are not compared.
+        unique_by: Vec<String>,
+    },
     #[serde(rename = "string[]")]
     /// An array of string values.
     ArrayString {
@@ -217,7 +1486,7 @@ pub enum TypeConstraints {
         #[serde(flatten)]
         constraints: CollectionConstraints,
         #[serde(flatten)]
-        inner_constraints: IntConstraints<f64>,
+        inner_constraints: FloatConstraints<f64>,
     },
     #[serde(rename = "f32[]")]
     /// An array of f32 values.
@@ -225,7 +1494,7 @@ pub enum TypeConstraints {
         #[serde(flatten)]
         constraints: CollectionConstraints,
         #[serde(flatten)]
-        inner_constraints: IntConstraints<f32>,
+        inner_constraints: FloatConstraints<f32>,
     },
 }
 
@@ -233,247 +1502,2270 @@ impl TypeConstraints {
     pub fn prompt(
         &self,
         field_name: &str,
-        can_skip: bool,
-        quiet: bool,
+        path: &str,
+        settings: ValueSettings,
+        options: &PromptOptions,
+        skip_remaining: &mut bool,
         populated_fields: &mut BTreeMap<String, serde_json::Value>,
-    ) -> io::Result<serde_json::Value> {
-        let theme = ColorfulTheme::default();
+    ) -> Result<serde_json::Value, Error> {
+        let field_name = &match self.range_hint() {
+            Some(hint) => format!("{field_name} {hint}"),
+            None => field_name.to_string(),
+        };
+        if let Some((items, radix)) = self.int_menu().filter(|_| io::stdin().is_terminal()) {
+            return prompt_int_menu(field_name, &items, radix, settings);
+        }
+
         match self {
-            TypeConstraints::Bool => bool::prompt(field_name, Some(BlankValidator), can_skip)
-                .map(serde_json::Value::from),
-            TypeConstraints::String(constraints) => {
-                String::prompt(field_name, Some(constraints.clone()), can_skip)
+            TypeConstraints::Bool(constraints)
+                if constraints.text_entry || !io::stdin().is_terminal() =>
+            {
+                String::prompt(
+                    field_name,
+                    Some(constraints.clone()),
+                    settings,
+                    skip_remaining,
+                )
+                .map(|value| {
+                    value
+                        .and_then(|v| constraints.parse(&v).ok())
+                        .map_or(serde_json::Value::Null, serde_json::Value::from)
+                })
+            }
+            TypeConstraints::Bool(_) => {
+                bool::prompt(field_name, Some(BlankValidator), settings, skip_remaining)
                     .map(serde_json::Value::from)
             }
+            TypeConstraints::String(constraints) => {
+                let field_name = match constraints.mask.as_ref() {
+                    Some(mask) => format!("{field_name} {mask}"),
+                    None => field_name.to_string(),
+                };
+                String::prompt(
+                    field_name,
+                    Some(constraints.clone()),
+                    settings,
+                    skip_remaining,
+                )
+                .map(|value| value.map_or(serde_json::Value::Null, |v| constraints.to_value(v)))
+            }
             TypeConstraints::U64(constraints) => {
-                u64::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                u64::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::U32(constraints) => {
-                u32::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                u32::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::U16(constraints) => {
-                u16::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                u16::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::U8(constraints) => {
-                u8::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                u8::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::I64(constraints) => {
-                i64::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                i64::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::I32(constraints) => {
-                i32::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                i32::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::I16(constraints) => {
-                i16::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                i16::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::I8(constraints) => {
-                i8::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                i8::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
             }
             TypeConstraints::F64(constraints) => {
-                f64::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                let settings = ValueSettings {
+                    max_decimal_places: constraints.max_decimal_places,
+                    ..settings
+                };
+                f64::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(|value| value.map_or(serde_json::Value::Null, |v| constraints.to_value(v)))
             }
             TypeConstraints::F32(constraints) => {
-                f32::prompt(field_name, Some(*constraints), can_skip).map(serde_json::Value::from)
+                let settings = ValueSettings {
+                    max_decimal_places: constraints.max_decimal_places,
+                    ..settings
+                };
+                f32::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(|value| value.map_or(serde_json::Value::Null, |v| constraints.to_value(v)))
+            }
+            TypeConstraints::ByteSize(constraints) => {
+                ByteSize::prompt(field_name, Some(*constraints), settings, skip_remaining)
+                    .map(serde_json::Value::from)
+            }
+            TypeConstraints::Money(constraints) => String::prompt(
+                field_name,
+                Some(constraints.clone()),
+                ValueSettings {
+                    default: settings
+                        .default
+                        .as_ref()
+                        .and_then(|value| constraints.stored_amount(value))
+                        .map(|amount| serde_json::Value::from(constraints.format(amount))),
+                    ..settings
+                },
+                skip_remaining,
+            )
+            .map(|value| value.map_or(serde_json::Value::Null, |v| constraints.to_value(&v))),
+            TypeConstraints::Section { .. } => Ok(serde_json::Value::Null),
+            TypeConstraints::Select { .. }
+            | TypeConstraints::OneOfBool { .. }
+            | TypeConstraints::TriBool
+            | TypeConstraints::Flags { .. }
+                if !io::stdin().is_terminal() =>
+            {
+                Err(Error::NotATerminal {
+                    field: field_name.to_string(),
+                })
             }
             TypeConstraints::Select {
                 constraints,
                 conditions,
             } => {
-                let items = constraints
-                    .items
-                    .iter()
-                    .map(display_value)
-                    .collect::<Vec<String>>();
+                let invalid_schema = |reason| Error::InvalidSchema {
+                    field: field_name.to_string(),
+                    reason,
+                };
+                let select_items = constraints
+                    .resolve_items(populated_fields)
+                    .map_err(invalid_schema)?;
+                if select_items.is_empty() && settings.can_skip {
+                    return Ok(serde_json::Value::Null);
+                } else if select_items.is_empty() {
+                    return Err(invalid_schema("There are no items to select".to_string()));
+                }
+
+                let default_index = constraints
+                    .resolve_default_index(&select_items)
+                    .map_err(invalid_schema)?
+                    .filter(|_| !constraints.select_many);
+
+                // A tagged default holds the selected item under the tag.
+                let untag = |value: &serde_json::Value| match constraints.tag.as_ref() {
+                    Some(tag) => value.get(tag).cloned(),
+                    None => Some(value.clone()),
+                };
+                let answer_default = settings.default.as_ref();
+                let default_index = answer_default
+                    .filter(|_| !constraints.select_many)
+                    .and_then(untag)
+                    .and_then(|default| select_items.iter().position(|item| *item == default))
+                    .or(default_index);
+                // Leaves room for the cursor and checkbox drawn by the theme.
+                let max_width = constraints.max_label_width.or_else(|| {
+                    let (_, columns) = Term::stdout().size_checked()?;
+                    Some((columns as usize).saturating_sub(4))
+                });
+                let items = select_labels(&select_items, default_index, max_width);
 
                 if constraints.select_many {
-                    let maybe_selections = MultiSelect::with_theme(&ColorfulTheme::default())
-                        .with_prompt(field_name)
-                        .items(&items)
-                        .defaults(&[])
-                        .interact_opt()?;
-
-                    let selections = match maybe_selections {
-                        None => return Ok(serde_json::Value::Null),
-                        Some(selections) => selections,
+                    let disabled = select_items
+                        .iter()
+                        .map(|item| constraints.is_disabled(item, populated_fields))
+                        .collect::<Vec<_>>();
+                    let disabled_style = Style::new().dim().for_stderr();
+                    let items = items
+                        .into_iter()
+                        .zip(disabled.iter())
+                        .map(|(label, &disabled)| {
+                            if disabled {
+                                let label = format!("{label} (unavailable)");
+                                disabled_style.apply_to(label).to_string()
+                            } else {
+                                label
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    // Escaping the menu skips the field, which is different
+                    // from confirming an empty selection.
+                    let selections = loop {
+                        let prompt = field_name.to_string();
+                        let items = items.clone();
+                        let checked = match answer_default.and_then(|v| v.as_array()) {
+                            None => Vec::new(),
+                            Some(defaults) => {
+                                let defaults =
+                                    defaults.iter().filter_map(untag).collect::<Vec<_>>();
+                                select_items
+                                    .iter()
+                                    .zip(disabled.iter())
+                                    .map(|(item, &disabled)| !disabled && defaults.contains(item))
+                                    .collect()
+                            }
+                        };
+                        let page_size = constraints.page_size;
+                        let maybe_selections = interact(field_name, settings.timeout, move || {
+                            let theme = ColorfulTheme::default();
+                            let mut select = MultiSelect::with_theme(&theme);
+                            select.with_prompt(prompt).items(&items).defaults(&checked);
+                            if let Some(page_size) = page_size {
+                                select.max_length(page_size);
+                            }
+                            select.interact_opt()
+                        })?;
+
+                        match maybe_selections {
+                            Some(selections) if selections.iter().any(|&i| disabled[i]) => {
+                                let error_style = Style::new().red().italic().for_stdout();
+                                println!(
+                                    "{}",
+                                    error_style.apply_to("Unavailable items cannot be selected.")
+                                );
+                            }
+                            Some(selections) => break selections,
+                            None if settings.can_skip => {
+                                let returned_value = check_skip_conditions(
+                                    path,
+                                    conditions,
+                                    options,
+                                    skip_remaining,
+                                    populated_fields,
+                                )?;
+                                return Ok(returned_value.unwrap_or(serde_json::Value::Null));
+                            }
+                            None => {
+                                let error_style = Style::new().red().italic().for_stdout();
+                                println!(
+                                    "{}",
+                                    error_style
+                                        .apply_to("This field is required and cannot be skipped.")
+                                );
+                            }
+                        }
                     };
 
                     let selections = selections
                         .into_iter()
-                        .flat_map(|index| constraints.items.get(index).cloned());
+                        .flat_map(|index| select_items.get(index).cloned())
+                        .collect::<Vec<_>>();
+
+                    let mut returned_values = Vec::new();
+                    for selected in selections.iter() {
+                        returned_values.push(check_conditions(
+                            path,
+                            conditions,
+                            |condition| !condition.once && condition.picked == *selected,
+                            options,
+                            skip_remaining,
+                            populated_fields,
+                        )?);
+                    }
+
+                    for condition in conditions.if_conditions.iter().filter(|c| c.once) {
+                        let index = match selections.iter().position(|s| *s == condition.picked) {
+                            None => continue,
+                            Some(index) => index,
+                        };
 
-                    let mut values = Vec::new();
-                    for selected in selections {
-                        let returned_value =
-                            check_conditions(conditions, &selected, quiet, populated_fields)?;
-                        values.push(returned_value.unwrap_or(selected));
+                        let returned_value = prompt_condition(
+                            path,
+                            conditions,
+                            condition,
+                            options,
+                            skip_remaining,
+                            populated_fields,
+                        )?;
+                        match (&mut returned_values[index], returned_value) {
+                            (
+                                Some(serde_json::Value::Object(existing)),
+                                Some(serde_json::Value::Object(object)),
+                            ) => existing.extend(object),
+                            (existing, returned_value) => {
+                                *existing = returned_value.or(existing.take())
+                            }
+                        }
                     }
 
+                    let values = selections
+                        .into_iter()
+                        .zip(returned_values)
+                        .map(|(selected, returned_value)| {
+                            constraints.to_value(selected, returned_value)
+                        })
+                        .collect();
                     return Ok(serde_json::Value::Array(values));
                 }
 
-                let selected_value = if can_skip {
-                    Select::with_theme(&theme)
-                        .with_prompt(field_name)
-                        .default(0)
-                        .items(&items)
-                        .interact_opt()?
-                        .and_then(|index| constraints.items.get(index).cloned())
-                        .unwrap_or(serde_json::Value::Null)
+                let prompt = field_name.to_string();
+                let can_skip = settings.can_skip;
+                let page_size = constraints.page_size;
+                let selected = interact(field_name, settings.timeout, move || {
+                    let theme = ColorfulTheme::default();
+                    let mut select = Select::with_theme(&theme);
+                    select
+                        .with_prompt(prompt)
+                        .default(default_index.unwrap_or(0))
+                        .items(&items);
+                    if let Some(page_size) = page_size {
+                        select.max_length(page_size);
+                    }
+                    if can_skip {
+                        select.interact_opt()
+                    } else {
+                        select.interact().map(Some)
+                    }
+                })?;
+                let selected_value = selected
+                    .and_then(|index| select_items.get(index).cloned())
+                    .unwrap_or(serde_json::Value::Null);
+
+                let returned_value = if selected_value.is_null() {
+                    check_skip_conditions(
+                        path,
+                        conditions,
+                        options,
+                        skip_remaining,
+                        populated_fields,
+                    )?
                 } else {
-                    let index = Select::with_theme(&theme)
-                        .with_prompt(field_name)
-                        .default(0)
-                        .items(&items)
-                        .interact()?;
-                    constraints
-                        .items
-                        .get(index)
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Null)
+                    check_conditions(
+                        path,
+                        conditions,
+                        |condition| condition.picked == selected_value,
+                        options,
+                        skip_remaining,
+                        populated_fields,
+                    )?
+                };
+                Ok(constraints.to_value(selected_value, returned_value))
+            }
+            TypeConstraints::OneOfBool { options, emit_name } => {
+                if options.is_empty() {
+                    return Err(Error::InvalidSchema {
+                        field: field_name.to_string(),
+                        reason: "There are no options to choose from".to_string(),
+                    });
+                }
+
+                let default_index = settings
+                    .default
+                    .as_ref()
+                    .and_then(|default| chosen_option(options, default));
+                let prompt = field_name.to_string();
+                let items = options.clone();
+                let can_skip = settings.can_skip;
+                let chosen = interact(field_name, settings.timeout, move || {
+                    let theme = ColorfulTheme::default();
+                    let mut select = Select::with_theme(&theme);
+                    select
+                        .with_prompt(prompt)
+                        .default(default_index.unwrap_or(0))
+                        .items(&items);
+                    if can_skip {
+                        select.interact_opt()
+                    } else {
+                        select.interact().map(Some)
+                    }
+                })?;
+
+                Ok(chosen.map_or(serde_json::Value::Null, |index| {
+                    one_of_bool_value(options, index, *emit_name)
+                }))
+            }
+            TypeConstraints::TriBool => {
+                let default_index = TRI_BOOL_VALUES
+                    .iter()
+                    .position(|value| Some(value) == settings.default.as_ref())
+                    .unwrap_or(2);
+                let prompt = field_name.to_string();
+                let can_skip = settings.can_skip;
+                let chosen = interact(field_name, settings.timeout, move || {
+                    let theme = ColorfulTheme::default();
+                    let mut select = Select::with_theme(&theme);
+                    select
+                        .with_prompt(prompt)
+                        .default(default_index)
+                        .items(&["Yes", "No", "Unknown"]);
+                    if can_skip {
+                        select.interact_opt()
+                    } else {
+                        select.interact().map(Some)
+                    }
+                })?;
+
+                Ok(chosen
+                    .and_then(|index| TRI_BOOL_VALUES.get(index).cloned())
+                    .unwrap_or(serde_json::Value::Null))
+            }
+            TypeConstraints::Flags { options } => {
+                let checked = options
+                    .iter()
+                    .map(|option| {
+                        settings
+                            .default
+                            .as_ref()
+                            .and_then(|default| default.get(option))
+                            .and_then(|flag| flag.as_bool())
+                            .unwrap_or(false)
+                    })
+                    .collect::<Vec<_>>();
+                let selections = loop {
+                    let prompt = field_name.to_string();
+                    let items = options.clone();
+                    let checked = checked.clone();
+                    let maybe_selections = interact(field_name, settings.timeout, move || {
+                        MultiSelect::with_theme(&ColorfulTheme::default())
+                            .with_prompt(prompt)
+                            .items(&items)
+                            .defaults(&checked)
+                            .interact_opt()
+                    })?;
+
+                    match maybe_selections {
+                        Some(selections) => break selections,
+                        None if settings.can_skip => return Ok(serde_json::Value::Null),
+                        None => {
+                            let error_style = Style::new().red().italic().for_stdout();
+                            println!(
+                                "{}",
+                                error_style
+                                    .apply_to("This field is required and cannot be skipped.")
+                            );
+                        }
+                    }
                 };
 
-                let returned_value =
-                    check_conditions(conditions, &selected_value, quiet, populated_fields)?;
-                Ok(returned_value.unwrap_or(selected_value))
+                let flags = options
+                    .iter()
+                    .enumerate()
+                    .map(|(i, option)| {
+                        (
+                            option.clone(),
+                            serde_json::Value::from(selections.contains(&i)),
+                        )
+                    })
+                    .collect();
+                Ok(serde_json::Value::Object(flags))
             }
             TypeConstraints::ArrayString {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, inner_constraints.clone()),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                inner_constraints.clone(),
+                |v| inner_constraints.to_value(v),
+            ),
             TypeConstraints::ArrayU64 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayU32 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayU16 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayU8 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayI64 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayI32 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayI16 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayI8 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                settings,
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                serde_json::Value::from,
+            ),
             TypeConstraints::ArrayF64 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                ValueSettings {
+                    max_decimal_places: inner_constraints.max_decimal_places,
+                    ..settings
+                },
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                |v| inner_constraints.to_value(v),
+            ),
             TypeConstraints::ArrayF32 {
                 constraints,
                 inner_constraints,
-            } => array_prompter(can_skip, field_name, constraints, *inner_constraints),
+            } => array_prompter(
+                field_name,
+                ValueSettings {
+                    max_decimal_places: inner_constraints.max_decimal_places,
+                    ..settings
+                },
+                skip_remaining,
+                constraints,
+                *inner_constraints,
+                |v| inner_constraints.to_value(v),
+            ),
             TypeConstraints::Object { fields } => {
-                let mut nested_fields = serde_json::Map::new();
-                for (key, field) in fields {
-                    let value = field.prompt(key, quiet, true, populated_fields)?;
-                    nested_fields.insert(key.clone(), value);
-                }
-                Ok(serde_json::Value::Object(nested_fields))
+                prompt_object(path, fields, options, skip_remaining, populated_fields)
             }
+            TypeConstraints::ArrayObject {
+                constraints,
+                fields,
+                unique_by,
+            } => object_array_prompter(
+                field_name,
+                path,
+                settings,
+                options,
+                skip_remaining,
+                constraints,
+                fields,
+                unique_by,
+                populated_fields,
+            ),
         }
     }
-}
-
-fn array_prompter<'a, V, T>(
-    can_skip: bool,
-    field_name: &str,
-    constraints: &CollectionConstraints,
-    validator: V,
-) -> io::Result<serde_json::Value>
-where
-    T: PromptValue<'a, V> + Debug,
-    V: Validator<T> + Clone + 'a,
-    V::Err: Display,
-{
-    let error_style = Style::new().red().italic().for_stdout();
-    let mut values = Vec::new();
-    for _ in 0..constraints.max_items {
-        let maybe_value = T::prompt(field_name, Some(validator.clone()), true)?;
-
-        match maybe_value {
-            Some(value) => values.push(value.into()),
-            None => {
-                if values.len() < constraints.min_items {
-                    let msg = format!(
-                        "This field requires a minimum of {} values to be provided. {}",
-                        constraints.min_items,
-                        if can_skip { SKIP_MESSAGE } else { "" }
-                    );
-
-                    println!("{}", error_style.apply_to(msg));
-                    if can_skip {
-                        let skip = Confirm::with_theme(&ColorfulTheme::default())
-                            .with_prompt("Skip this field?")
-                            .interact()?;
-
-                        if skip {
-                            break;
-                        }
-                    }
 
-                    continue;
-                }
+    /// The allowed range of a number shown after the prompt.
+    fn range_hint(&self) -> Option<String> {
+        match self {
+            TypeConstraints::U64(c) => c.range_hint(),
+            TypeConstraints::U32(c) => c.range_hint(),
+            TypeConstraints::U16(c) => c.range_hint(),
+            TypeConstraints::U8(c) => c.range_hint(),
+            TypeConstraints::I64(c) => c.range_hint(),
+            TypeConstraints::I32(c) => c.range_hint(),
+            TypeConstraints::I16(c) => c.range_hint(),
+            TypeConstraints::I8(c) => c.range_hint(),
+            TypeConstraints::F64(c) => c.range_hint(),
+            TypeConstraints::F32(c) => c.range_hint(),
+            _ => None,
+        }
+    }
 
-                break;
-            }
+    /// The values and radix of an integer range shown as a menu.
+    fn int_menu(&self) -> Option<(Vec<i128>, Radix)> {
+        match self {
+            TypeConstraints::U64(c) => Some((c.menu_items()?, c.display_radix)),
+            TypeConstraints::U32(c) => Some((c.menu_items()?, c.display_radix)),
+            TypeConstraints::U16(c) => Some((c.menu_items()?, c.display_radix)),
+            TypeConstraints::U8(c) => Some((c.menu_items()?, c.display_radix)),
+            TypeConstraints::I64(c) => Some((c.menu_items()?, c.display_radix)),
+            TypeConstraints::I32(c) => Some((c.menu_items()?, c.display_radix)),
+            TypeConstraints::I16(c) => Some((c.menu_items()?, c.display_radix)),
+            TypeConstraints::I8(c) => Some((c.menu_items()?, c.display_radix)),
+            _ => None,
         }
     }
 
-    Ok(serde_json::Value::from(values))
-}
+    /// Is the type a single value rather than a select, object or array.
+    pub fn is_scalar(&self) -> bool {
+        matches!(
+            self,
+            TypeConstraints::Bool(_)
+                | TypeConstraints::String(_)
+                | TypeConstraints::U64(_)
+                | TypeConstraints::U32(_)
+                | TypeConstraints::U16(_)
+                | TypeConstraints::U8(_)
+                | TypeConstraints::I64(_)
+                | TypeConstraints::I32(_)
+                | TypeConstraints::I16(_)
+                | TypeConstraints::I8(_)
+                | TypeConstraints::F64(_)
+                | TypeConstraints::F32(_)
+                | TypeConstraints::ByteSize(_)
+                | TypeConstraints::Money(_)
+        )
+    }
 
-fn check_conditions(
-    conditions: &Conditions,
-    selected: &serde_json::Value,
-    quiet: bool,
-    populated_fields: &mut BTreeMap<String, serde_json::Value>,
-) -> io::Result<Option<serde_json::Value>> {
-    let mut return_value = None;
-    for condition in conditions.if_conditions.iter() {
-        if &condition.picked != selected {
-            continue;
+    /// The warnings about an accepted value, which do not reject it.
+    pub fn warnings(&self, value: &serde_json::Value) -> Vec<String> {
+        match self {
+            TypeConstraints::String(constraints) => value
+                .as_str()
+                .map(|value| constraints.warnings(value))
+                .unwrap_or_default(),
+            TypeConstraints::U64(constraints) => int_warning(constraints, value),
+            TypeConstraints::U32(constraints) => int_warning(constraints, value),
+            TypeConstraints::U16(constraints) => int_warning(constraints, value),
+            TypeConstraints::U8(constraints) => int_warning(constraints, value),
+            TypeConstraints::I64(constraints) => int_warning(constraints, value),
+            TypeConstraints::I32(constraints) => int_warning(constraints, value),
+            TypeConstraints::I16(constraints) => int_warning(constraints, value),
+            TypeConstraints::I8(constraints) => int_warning(constraints, value),
+            _ => Vec::new(),
         }
+    }
 
-        let mut object = serde_json::Map::new();
-        for (key, field) in condition.fields.iter() {
-            let value = field.prompt(key, quiet, false, populated_fields)?;
-
-            if conditions.insert_at_root {
-                populated_fields.insert(key.clone(), value);
-            } else {
-                object.insert(key.clone(), value);
+    /// A sample value which satisfies the constraints, see [Schema::example].
+    ///
+    /// Nested fields are shown based on the root answers given.
+    pub fn example(&self, answers: &BTreeMap<String, serde_json::Value>) -> serde_json::Value {
+        match self {
+            TypeConstraints::Bool(_) => serde_json::Value::from(false),
+            TypeConstraints::String(constraints) => constraints.example(),
+            TypeConstraints::U64(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::U32(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::U16(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::U8(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::I64(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::I32(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::I16(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::I8(constraints) => serde_json::Value::from(constraints.min),
+            TypeConstraints::F64(constraints) => constraints.example(),
+            TypeConstraints::F32(constraints) => constraints.example(),
+            TypeConstraints::ByteSize(constraints) => {
+                serde_json::Value::from(constraints.min.unwrap_or(ByteSize(0)))
             }
-        }
+            TypeConstraints::Money(constraints) => constraints.example(),
+            TypeConstraints::Section { .. } => serde_json::Value::Null,
+            TypeConstraints::Select {
+                constraints,
+                conditions,
+            } => {
+                let item = match constraints.items.first() {
+                    None => return serde_json::Value::Null,
+                    Some(item) => item.clone(),
+                };
+                let condition_value = conditions
+                    .if_conditions
+                    .iter()
+                    .find(|condition| condition.picked == item)
+                    .filter(|_| !conditions.insert_at_root)
+                    .map(|condition| example_object(&condition.fields, answers));
+                let value = constraints.to_value(item, condition_value);
 
-        if !conditions.insert_at_root {
-            return_value = Some(serde_json::Value::Object(object));
+                if constraints.select_many {
+                    serde_json::Value::Array(vec![value])
+                } else {
+                    value
+                }
+            }
+            TypeConstraints::OneOfBool { options, emit_name } if !options.is_empty() => {
+                one_of_bool_value(options, 0, *emit_name)
+            }
+            TypeConstraints::OneOfBool { .. } => serde_json::Value::Null,
+            TypeConstraints::TriBool => serde_json::Value::from(true),
+            TypeConstraints::Flags { options } => {
+                let flags = options
+                    .iter()
+                    .map(|option| (option.clone(), serde_json::Value::from(false)))
+                    .collect();
+                serde_json::Value::Object(flags)
+            }
+            TypeConstraints::Object { fields } => example_object(fields, answers),
+            TypeConstraints::ArrayObject {
+                constraints,
+                fields,
+                unique_by,
+            } => example_array(constraints, |index| {
+                let mut item = example_object(fields, answers);
+                for (key, field) in fields.iter() {
+                    let output_key = field.output_key(key);
+                    if unique_by.iter().any(|unique| unique == output_key) {
+                        item[output_key] = field.type_constraints.nth_example(answers, index);
+                    }
+                }
+                item
+            }),
+            TypeConstraints::ArrayString {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |_| inner_constraints.example()),
+            TypeConstraints::ArrayU64 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayU32 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayU16 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayU8 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayI64 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayI32 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayI16 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayI8 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| {
+                serde_json::Value::from(inner_constraints.nth_example(index))
+            }),
+            TypeConstraints::ArrayF64 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| inner_constraints.nth_example(index)),
+            TypeConstraints::ArrayF32 {
+                constraints,
+                inner_constraints,
+            } => example_array(constraints, |index| inner_constraints.nth_example(index)),
         }
-
-        break;
     }
 
-    Ok(return_value)
-}
-
-fn display_value(v: &serde_json::Value) -> String {
+    /// The `index`th of a sequence of distinct sample values, for array
+    /// items which must differ.
+    ///
+    /// Types without such a sequence repeat [TypeConstraints::example].
+    fn nth_example(
+        &self,
+        answers: &BTreeMap<String, serde_json::Value>,
+        index: usize,
+    ) -> serde_json::Value {
+        match self {
+            TypeConstraints::String(constraints) => constraints.nth_example(index),
+            TypeConstraints::U64(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::U32(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::U16(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::U8(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::I64(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::I32(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::I16(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::I8(constraints) => {
+                serde_json::Value::from(constraints.nth_example(index))
+            }
+            TypeConstraints::F64(constraints) => constraints.nth_example(index),
+            TypeConstraints::F32(constraints) => constraints.nth_example(index),
+            _ => self.example(answers),
+        }
+    }
+
+    /// Validates an already parsed value against the constraints
+    /// without prompting the user.
+    ///
+    /// Fields inserted at the root by a select's conditions are not
+    /// part of the value and so are not validated.
+    pub fn validate_value(&self, value: &serde_json::Value) -> Result<(), String> {
+        match self {
+            TypeConstraints::Bool(_) => validate_json::<bool, _>(value, BlankValidator),
+            TypeConstraints::String(constraints) => {
+                validate_json::<String, _>(value, constraints.clone())
+            }
+            TypeConstraints::U64(constraints) => validate_json::<u64, _>(value, *constraints),
+            TypeConstraints::U32(constraints) => validate_json::<u32, _>(value, *constraints),
+            TypeConstraints::U16(constraints) => validate_json::<u16, _>(value, *constraints),
+            TypeConstraints::U8(constraints) => validate_json::<u8, _>(value, *constraints),
+            TypeConstraints::I64(constraints) => validate_json::<i64, _>(value, *constraints),
+            TypeConstraints::I32(constraints) => validate_json::<i32, _>(value, *constraints),
+            TypeConstraints::I16(constraints) => validate_json::<i16, _>(value, *constraints),
+            TypeConstraints::I8(constraints) => validate_json::<i8, _>(value, *constraints),
+            TypeConstraints::F64(constraints) => {
+                validate_json::<f64, _>(&parse_float_string(value), *constraints)
+            }
+            TypeConstraints::F32(constraints) => {
+                validate_json::<f32, _>(&parse_float_string(value), *constraints)
+            }
+            TypeConstraints::ByteSize(constraints) => {
+                validate_json::<ByteSize, _>(value, *constraints)
+            }
+            TypeConstraints::Money(constraints) => constraints.validate_value(value),
+            TypeConstraints::Section { .. } => Ok(()),
+            TypeConstraints::Select {
+                constraints,
+                conditions,
+            } => {
+                if constraints.select_many {
+                    let values = value
+                        .as_array()
+                        .ok_or_else(|| format!("Value {value} is not an array"))?;
+                    for value in values {
+                        validate_selected(constraints, conditions, value)?;
+                    }
+                    Ok(())
+                } else {
+                    validate_selected(constraints, conditions, value)
+                }
+            }
+            TypeConstraints::OneOfBool { options, emit_name } => {
+                validate_one_of_bool(options, *emit_name, value)
+            }
+            TypeConstraints::TriBool => match value {
+                serde_json::Value::Bool(_) | serde_json::Value::Null => Ok(()),
+                value => Err(format!("Value {value} is not a boolean or null")),
+            },
+            TypeConstraints::Flags { options } => validate_flags(options, value).map(|_| ()),
+            TypeConstraints::Object { fields } => {
+                let object = value
+                    .as_object()
+                    .ok_or_else(|| format!("Value {value} is not an object"))?;
+                validate_object(fields, object)
+            }
+            TypeConstraints::ArrayObject {
+                constraints,
+                fields,
+                unique_by,
+            } => {
+                let values = value
+                    .as_array()
+                    .ok_or_else(|| format!("Value {value} is not an array"))?;
+                validate_item_count(values.len(), constraints)?;
+
+                for value in values {
+                    let object = value
+                        .as_object()
+                        .ok_or_else(|| format!("Value {value} is not an object"))?;
+                    validate_object(fields, object)?;
+                }
+                validate_unique_by(values, unique_by)
+            }
+            TypeConstraints::ArrayString {
+                constraints,
+                inner_constraints,
+            } => validate_array::<String, _>(value, constraints, inner_constraints.clone()),
+            TypeConstraints::ArrayU64 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<u64, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayU32 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<u32, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayU16 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<u16, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayU8 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<u8, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayI64 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<i64, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayI32 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<i32, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayI16 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<i16, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayI8 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<i8, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayF64 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<f64, _>(value, constraints, *inner_constraints),
+            TypeConstraints::ArrayF32 {
+                constraints,
+                inner_constraints,
+            } => validate_array::<f32, _>(value, constraints, *inner_constraints),
+        }
+    }
+}
+
+fn array_prompter<'a, V, T>(
+    field_name: &str,
+    settings: ValueSettings,
+    skip_remaining: &mut bool,
+    constraints: &CollectionConstraints,
+    validator: V,
+    into_value: impl Fn(T) -> serde_json::Value,
+) -> Result<serde_json::Value, Error>
+where
+    T: PromptValue<'a, V> + Debug + Clone + FromStr,
+    T::Err: Display,
+    V: Validator<T> + Clone + 'a,
+    V::Err: Display,
+{
+    if constraints.editor {
+        let items = read_editor(
+            field_name.replace("{index}", ""),
+            &settings,
+            Some(validator),
+            |items: &[T]| {
+                validate_item_count(items.len(), constraints)?;
+                let values = items.iter().cloned().map(&into_value).collect::<Vec<_>>();
+                constraints.validate_total_length(&values)?;
+                constraints.validate_order(&values)
+            },
+        )?;
+        return Ok(items.map_or(serde_json::Value::Null, |items| {
+            serde_json::Value::Array(items.into_iter().map(into_value).collect())
+        }));
+    }
+
+    if constraints.inline {
+        let items = read_inline(
+            field_name.replace("{index}", ""),
+            &settings,
+            constraints,
+            Some(validator),
+            |items: &[T]| {
+                validate_item_count(items.len(), constraints)?;
+                let values = items.iter().cloned().map(&into_value).collect::<Vec<_>>();
+                constraints.validate_total_length(&values)?;
+                constraints.validate_order(&values)
+            },
+            skip_remaining,
+        )?;
+        return Ok(match items {
+            Some(items) if !*skip_remaining => {
+                serde_json::Value::Array(items.into_iter().map(into_value).collect())
+            }
+            _ => serde_json::Value::Null,
+        });
+    }
+
+    let mut error_line = ErrorLine::default();
+    let mut values = Vec::new();
+    while values.len() < constraints.max_items {
+        let label = array_label(field_name, values.len());
+        let maybe_value = T::prompt(
+            &label,
+            Some(validator.clone()),
+            ValueSettings {
+                can_skip: true,
+                default: None,
+                allow_null: false,
+                ..settings.clone()
+            },
+            skip_remaining,
+        )?;
+        if *skip_remaining {
+            return Ok(serde_json::Value::Null);
+        }
+
+        match maybe_value {
+            Some(value) => {
+                let value = into_value(value);
+                values.push(value.clone());
+                let valid = constraints
+                    .validate_total_length(&values)
+                    .and_then(|_| constraints.validate_order(&values));
+                match valid {
+                    Ok(()) => error_line.clear(&label, &display_value(&value))?,
+                    Err(e) => {
+                        values.pop();
+                        error_line.show(&e)?;
+                    }
+                }
+            }
+            None => match blank_item(values.len(), constraints, settings.can_skip) {
+                BlankItem::Finish => break,
+                BlankItem::ConfirmSkip(msg) => {
+                    error_line.show(&msg)?;
+                    error_line.keep();
+                    let skip = interact(field_name, settings.timeout, || {
+                        Confirm::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Skip this field?")
+                            .interact()
+                    })?;
+
+                    if skip {
+                        break;
+                    }
+                }
+                BlankItem::MoreRequired(msg) => error_line.show(&msg)?,
+            },
+        }
+    }
+
+    if constraints.allow_reorder && values.len() > 1 && io::stdin().is_terminal() {
+        values = reorder_values(field_name, settings.timeout, values)?;
+    }
+    Ok(serde_json::Value::from(values))
+}
+
+/// What a blank item entered after `count` items does to an array.
+#[derive(Debug, PartialEq)]
+enum BlankItem {
+    /// The array is finished.
+    Finish,
+    /// Ask whether to skip the field, showing the message first.
+    ConfirmSkip(String),
+    /// The minimum is not met, the message says how many are missing.
+    MoreRequired(String),
+}
+
+fn blank_item(count: usize, constraints: &CollectionConstraints, can_skip: bool) -> BlankItem {
+    if count >= constraints.min_items {
+        return BlankItem::Finish;
+    }
+
+    // Only an empty array may be skipped, once items are entered
+    // the minimum is enforced.
+    if count == 0 && can_skip {
+        return BlankItem::ConfirmSkip(format!(
+            "This field requires a minimum of {} values to be provided. {SKIP_MESSAGE}",
+            constraints.min_items,
+        ));
+    }
+
+    let remaining = constraints.min_items - count;
+    BlankItem::MoreRequired(format!(
+        "{remaining} more {} required, this field needs at least {}.",
+        if remaining == 1 {
+            "value is"
+        } else {
+            "values are"
+        },
+        constraints.min_items,
+    ))
+}
+
+/// Lets the user move the entered items up and down, escaping keeps
+/// the order they were entered in.
+fn reorder_values(
+    field_name: &str,
+    timeout: Option<Duration>,
+    values: Vec<serde_json::Value>,
+) -> Result<Vec<serde_json::Value>, Error> {
+    let prompt = format!(
+        "{}: order the items",
+        field_name.replace("{index}", "").trim()
+    );
+    let items = values.iter().map(display_value).collect::<Vec<_>>();
+    let order = interact(field_name, timeout, move || {
+        Sort::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(&items)
+            .interact_opt()
+    })?;
+
+    Ok(match order {
+        Some(order) => order
+            .into_iter()
+            .map(|index| values[index].clone())
+            .collect(),
+        None => values,
+    })
+}
+
+fn prompt_object(
+    path: &str,
+    fields: &IndexMap<String, Field>,
+    options: &PromptOptions,
+    skip_remaining: &mut bool,
+    populated_fields: &mut BTreeMap<String, serde_json::Value>,
+) -> Result<serde_json::Value, Error> {
+    let mut nested_fields = serde_json::Map::new();
+    for (key, field) in fields {
+        if !field.is_visible(populated_fields) {
+            continue;
+        }
+
+        let path = format!("{path}.{key}");
+        let value = field.prompt(&path, options, true, skip_remaining, populated_fields)?;
+        if !field.is_section() {
+            nested_fields.insert(field.output_key(key).to_string(), value);
+        }
+    }
+    Ok(serde_json::Value::Object(nested_fields))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn object_array_prompter(
+    field_name: &str,
+    path: &str,
+    settings: ValueSettings,
+    options: &PromptOptions,
+    skip_remaining: &mut bool,
+    constraints: &CollectionConstraints,
+    fields: &IndexMap<String, Field>,
+    unique_by: &[String],
+    populated_fields: &mut BTreeMap<String, serde_json::Value>,
+) -> Result<serde_json::Value, Error> {
+    let mut values = Vec::new();
+    while values.len() < constraints.max_items {
+        let can_stop =
+            values.len() >= constraints.min_items || (settings.can_skip && values.is_empty());
+        if can_stop {
+            let prompt = format!(
+                "Add {} {} item?",
+                if values.is_empty() { "a" } else { "another" },
+                array_label(field_name, values.len()),
+            );
+            let default = values.len() < constraints.min_items;
+            let add = interact(field_name, settings.timeout, move || {
+                Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt(prompt)
+                    .default(default)
+                    .interact()
+            })?;
+
+            if !add && values.len() < constraints.min_items {
+                return Ok(serde_json::Value::Null);
+            } else if !add {
+                break;
+            }
+        }
+
+        let path = format!("{path}[{}]", values.len());
+        let value = prompt_object(&path, fields, options, skip_remaining, populated_fields)?;
+        if *skip_remaining {
+            return Ok(serde_json::Value::Null);
+        }
+
+        if let Some(index) = duplicate_of(&values, &value, unique_by) {
+            let error_style = Style::new().red().italic().for_stdout();
+            let message = duplicate_message(values.len(), index, &value, unique_by);
+            println!("{}", error_style.apply_to(message));
+            continue;
+        }
+
+        values.push(value);
+    }
+
+    Ok(serde_json::Value::Array(values))
+}
+
+/// The index of the earlier item sharing the values of the unique keys.
+fn duplicate_of(
+    values: &[serde_json::Value],
+    value: &serde_json::Value,
+    unique_by: &[String],
+) -> Option<usize> {
+    if unique_by.is_empty() {
+        return None;
+    }
+
+    let key_values = |value: &serde_json::Value| {
+        unique_by
+            .iter()
+            .map(|key| value.get(key).cloned().unwrap_or(serde_json::Value::Null))
+            .collect::<Vec<_>>()
+    };
+    let unique_values = key_values(value);
+    if unique_values.iter().all(serde_json::Value::is_null) {
+        return None;
+    }
+    values
+        .iter()
+        .position(|existing| key_values(existing) == unique_values)
+}
+
+fn duplicate_message(
+    index: usize,
+    existing: usize,
+    value: &serde_json::Value,
+    unique_by: &[String],
+) -> String {
+    let conflict = unique_by
+        .iter()
+        .map(|key| {
+            let key_value = value.get(key).unwrap_or(&serde_json::Value::Null);
+            format!("{key} {key_value}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "Item {} has the same {conflict} as item {}",
+        index + 1,
+        existing + 1
+    )
+}
+
+fn validate_unique_by(values: &[serde_json::Value], unique_by: &[String]) -> Result<(), String> {
+    for (index, value) in values.iter().enumerate() {
+        if let Some(existing) = duplicate_of(&values[..index], value, unique_by) {
+            return Err(duplicate_message(index, existing, value, unique_by));
+        }
+    }
+    Ok(())
+}
+
+/// Renders the label for the array element at the given index.
+fn array_label(field_name: &str, index: usize) -> String {
+    field_name.replace("{index}", &(index + 1).to_string())
+}
+
+fn check_conditions(
+    path: &str,
+    conditions: &Conditions,
+    matches: impl Fn(&IfCondition) -> bool,
+    options: &PromptOptions,
+    skip_remaining: &mut bool,
+    populated_fields: &mut BTreeMap<String, serde_json::Value>,
+) -> Result<Option<serde_json::Value>, Error> {
+    match conditions
+        .if_conditions
+        .iter()
+        .find(|condition| matches(condition))
+    {
+        Some(condition) => prompt_condition(
+            path,
+            conditions,
+            condition,
+            options,
+            skip_remaining,
+            populated_fields,
+        ),
+        None => Ok(None),
+    }
+}
+
+/// Prompts the fields of a triggered condition, returning the object
+/// holding them unless they are inserted at the root.
+fn prompt_condition(
+    path: &str,
+    conditions: &Conditions,
+    condition: &IfCondition,
+    options: &PromptOptions,
+    skip_remaining: &mut bool,
+    populated_fields: &mut BTreeMap<String, serde_json::Value>,
+) -> Result<Option<serde_json::Value>, Error> {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(path, picked = %condition.picked, "condition matched");
+
+    if let Some(message) = condition.confirm.as_ref().filter(|_| !*skip_remaining) {
+        let prompt = message.clone();
+        let proceed = interact(message, options.timeout, move || {
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .default(true)
+                .interact()
+        })?;
+
+        if !proceed {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(path, "condition declined");
+            return Ok(None);
+        }
+    }
+
+    prompt_condition_fields(
+        path,
+        conditions,
+        &condition.fields,
+        &condition.picked,
+        options,
+        skip_remaining,
+        populated_fields,
+    )
+}
+
+/// Prompts the fields of a condition which was skipped, if the
+/// conditions have fields to prompt on skip.
+fn check_skip_conditions(
+    path: &str,
+    conditions: &Conditions,
+    options: &PromptOptions,
+    skip_remaining: &mut bool,
+    populated_fields: &mut BTreeMap<String, serde_json::Value>,
+) -> Result<Option<serde_json::Value>, Error> {
+    let fields = match conditions.on_skip.as_ref() {
+        Some(fields) if !*skip_remaining => fields,
+        _ => return Ok(None),
+    };
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(path, "skip condition matched");
+
+    prompt_condition_fields(
+        path,
+        conditions,
+        fields,
+        &serde_json::Value::Null,
+        options,
+        skip_remaining,
+        populated_fields,
+    )
+}
+
+fn prompt_condition_fields(
+    path: &str,
+    conditions: &Conditions,
+    fields: &IndexMap<String, Field>,
+    selected: &serde_json::Value,
+    options: &PromptOptions,
+    skip_remaining: &mut bool,
+    populated_fields: &mut BTreeMap<String, serde_json::Value>,
+) -> Result<Option<serde_json::Value>, Error> {
+    let mut object = serde_json::Map::new();
+    for (key, field) in fields.iter() {
+        if !field.is_visible(populated_fields) {
+            continue;
+        }
+
+        let path = if conditions.insert_at_root {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+        let value = field.prompt_with_selected(
+            &path,
+            options,
+            false,
+            Some(selected),
+            skip_remaining,
+            populated_fields,
+        )?;
+
+        if field.is_section() {
+            continue;
+        } else if conditions.insert_at_root {
+            populated_fields.insert(field.output_key(key).to_string(), value);
+        } else {
+            object.insert(field.output_key(key).to_string(), value);
+        }
+    }
+
+    if conditions.insert_at_root {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::Value::Object(object)))
+    }
+}
+
+/// Records a top level field as omitted if the user omitted it, or
+/// one of its nested fields, and omission is allowed.
+fn omit_field(
+    result: Result<serde_json::Value, Error>,
+    key: &str,
+    allow_omit: bool,
+    omitted: &mut Vec<String>,
+) -> Result<serde_json::Value, Error> {
+    match result {
+        Err(Error::FieldOmitted { .. }) if allow_omit => {
+            omitted.push(key.to_string());
+            Ok(serde_json::Value::Null)
+        }
+        Err(Error::FieldOmitted { .. }) => Err(Error::RequiredFieldSkipped {
+            field: key.to_string(),
+        }),
+        result => result,
+    }
+}
+
+/// Prints a section or group heading.
+fn print_heading(title: &str) {
+    let styled = Style::new()
+        .bold()
+        .cyan()
+        .for_stdout()
+        .apply_to(format!("── {title} ──"));
+    println!("\n{styled}");
+}
+
+/// Prints a description, a page at a time if it is longer than the
+/// threshold and the output is a terminal.
+///
+/// Lines are wrapped to the width of the terminal.
+fn print_description(description: &str, page_over: Option<usize>) -> io::Result<()> {
+    let styled = Style::new().dim().italic().for_stdout();
+    let term = Term::stdout();
+    let lines = match term.size_checked() {
+        Some((_, columns)) => description
+            .lines()
+            .flat_map(|line| wrap_line(line, (columns as usize).saturating_sub(2)))
+            .collect::<Vec<_>>(),
+        None => description.lines().map(str::to_string).collect(),
+    };
+    let paged = page_over.is_some_and(|threshold| lines.len() > threshold) && term.is_term();
+    if !paged {
+        for line in lines {
+            println!("  {}", styled.apply_to(line));
+        }
+        return Ok(());
+    }
+
+    // Leave a row for the hint so a page never scrolls off the screen.
+    let page_height = (term.size().0 as usize).saturating_sub(1).max(1);
+    let mut pages = lines.chunks(page_height).peekable();
+    while let Some(page) = pages.next() {
+        for line in page {
+            term.write_line(&format!("  {}", styled.apply_to(line)))?;
+        }
+
+        if pages.peek().is_some() {
+            let hint_style = Style::new().dim().for_stdout();
+            term.write_str(
+                &hint_style
+                    .apply_to("-- Press space to continue --")
+                    .to_string(),
+            )?;
+            while !matches!(term.read_key()?, Key::Char(' ') | Key::Enter) {}
+            term.clear_line()?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits the line at spaces into lines which fit within the width, a
+/// word longer than the width is split across lines.
+///
+/// The leading whitespace of the line is kept, i.e. for indented list
+/// items, if it leaves room for the text.
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let text = line.trim_start();
+    let indent = &line[..line.len() - text.len()];
+    let mut current = String::new();
+    if measure_text_width(indent) < width {
+        current.push_str(indent);
+    }
+
+    let mut lines = Vec::new();
+    let mut line_start = current.len();
+    for word in text.split(' ') {
+        let separator = usize::from(current.len() > line_start);
+        if measure_text_width(&current) + separator + measure_text_width(word) <= width {
+            if separator == 1 {
+                current.push(' ');
+            }
+            current.push_str(word);
+            continue;
+        }
+
+        if current.len() > line_start {
+            lines.push(std::mem::take(&mut current));
+            line_start = 0;
+        }
+        let mut word = word;
+        while measure_text_width(&current) + measure_text_width(word) > width {
+            let split = split_at_width(word, width - measure_text_width(&current));
+            current.push_str(&word[..split]);
+            lines.push(std::mem::take(&mut current));
+            line_start = 0;
+            word = &word[split..];
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+/// The byte index where the text stops fitting within the display width,
+/// at least one character is always included.
+fn split_at_width(text: &str, width: usize) -> usize {
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        used += measure_text_width(c.encode_utf8(&mut [0; 4]));
+        if used > width && index > 0 {
+            return index;
+        }
+    }
+    text.len()
+}
+
+/// Prints the text inside a bordered box.
+fn print_boxed(text: &str) {
+    let lines = text.lines().map(str::trim_end).collect::<Vec<_>>();
+    let width = match lines.iter().map(|line| measure_text_width(line)).max() {
+        None => return,
+        Some(width) => width,
+    };
+
+    let border_style = Style::new().dim().for_stdout();
+    let text_style = Style::new().italic().for_stdout();
+    let border = "─".repeat(width + 2);
+    println!("  {}", border_style.apply_to(format!("┌{border}┐")));
+    for line in lines {
+        let padding = " ".repeat(width - measure_text_width(line));
+        println!(
+            "  {} {}{padding} {}",
+            border_style.apply_to("│"),
+            text_style.apply_to(line),
+            border_style.apply_to("│"),
+        );
+    }
+    println!("  {}", border_style.apply_to(format!("└{border}┘")));
+}
+
+/// Validates every field of the object, collecting all of the errors.
+///
+/// Fields of conditions which are inserted at the root are looked up in `answers`.
+fn collect_errors(
+    fields: &IndexMap<String, Field>,
+    object: &serde_json::Map<String, serde_json::Value>,
+    answers: &BTreeMap<String, serde_json::Value>,
+    path: &str,
+    errors: &mut Vec<(String, String)>,
+) {
+    for (key, field) in fields {
+        if field.is_section() || !field.is_visible(answers) {
+            continue;
+        }
+
+        let path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+        let value = object
+            .get(field.output_key(key))
+            .unwrap_or(&serde_json::Value::Null);
+
+        match (&field.type_constraints, value) {
+            (TypeConstraints::Object { fields }, serde_json::Value::Object(nested)) => {
+                collect_errors(fields, nested, answers, &path, errors);
+            }
+            (
+                TypeConstraints::ArrayObject {
+                    constraints,
+                    fields,
+                    unique_by,
+                },
+                serde_json::Value::Array(values),
+            ) => {
+                if let Err(e) = validate_item_count(values.len(), constraints) {
+                    errors.push((path.clone(), e));
+                }
+                if let Err(e) = validate_unique_by(values, unique_by) {
+                    errors.push((path.clone(), e));
+                }
+
+                for (index, value) in values.iter().enumerate() {
+                    let path = format!("{path}[{index}]");
+                    match value.as_object() {
+                        Some(nested) => collect_errors(fields, nested, answers, &path, errors),
+                        None => errors.push((path, format!("Value {value} is not an object"))),
+                    }
+                }
+            }
+            _ => {
+                if let Err(e) = field.validate_value(value) {
+                    errors.push((path, e));
+                }
+            }
+        }
+
+        if let TypeConstraints::Select { conditions, .. } = &field.type_constraints {
+            if !conditions.insert_at_root {
+                continue;
+            }
+
+            let selected = value
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or(std::slice::from_ref(value));
+            let triggered = conditions
+                .if_conditions
+                .iter()
+                .filter(|condition| selected.contains(&condition.picked))
+                .map(|condition| &condition.fields)
+                .chain(conditions.on_skip.iter().filter(|_| value.is_null()));
+            for condition_fields in triggered {
+                let root = answers
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                collect_errors(condition_fields, &root, answers, "", errors);
+            }
+        }
+    }
+}
+
+/// Collects the `copy_from` paths of the field and those nested within it.
+fn collect_copy_from<'a>(path: &str, field: &'a Field, copies: &mut Vec<(String, &'a str)>) {
+    if let Some(source) = field.copy_from.as_ref() {
+        copies.push((path.to_string(), source));
+    }
+
+    let nested = match &field.type_constraints {
+        TypeConstraints::Object { fields } | TypeConstraints::ArrayObject { fields, .. } => {
+            vec![fields]
+        }
+        TypeConstraints::Select { conditions, .. } => conditions.all_fields().collect(),
+        _ => Vec::new(),
+    };
+    for (key, field) in nested.into_iter().flat_map(|fields| fields.iter()) {
+        collect_copy_from(&format!("{path}.{key}"), field, copies);
+    }
+}
+
+/// Collects the problems with the fields themselves.
+fn collect_schema_errors(
+    fields: &IndexMap<String, Field>,
+    path: &str,
+    errors: &mut Vec<(String, String)>,
+) {
+    for (key, field) in fields {
+        let path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+
+        match &field.type_constraints {
+            TypeConstraints::String(constraints)
+            | TypeConstraints::ArrayString {
+                inner_constraints: constraints,
+                ..
+            } => {
+                if let Err(e) = constraints.check_patterns() {
+                    errors.push((path, e));
+                }
+            }
+            TypeConstraints::Object { fields } | TypeConstraints::ArrayObject { fields, .. } => {
+                collect_schema_errors(fields, &path, errors);
+            }
+            TypeConstraints::Select {
+                constraints,
+                conditions,
+            } => {
+                if let Err(e) = constraints.check_items() {
+                    errors.push((path.clone(), e));
+                }
+                let dynamic =
+                    constraints.items_command.is_some() || constraints.items_from_field.is_some();
+                if constraints.items.is_empty() && !dynamic && !field.can_skip {
+                    let reason = "There are no items to select".to_string();
+                    errors.push((path.clone(), reason));
+                }
+                for condition_fields in conditions.all_fields() {
+                    let path = if conditions.insert_at_root { "" } else { &path };
+                    collect_schema_errors(condition_fields, path, errors);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_schema_warnings(
+    fields: &IndexMap<String, Field>,
+    path: &str,
+    warnings: &mut Vec<(String, String)>,
+) {
+    for (key, field) in fields {
+        let path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+
+        match &field.type_constraints {
+            TypeConstraints::String(constraints)
+                if !field.can_skip && constraints.clone().validate(&String::new()).is_ok() =>
+            {
+                let reason = "The field is required but its constraints accept an empty \
+                              string, set min_length to 1 to reject it";
+                warnings.push((path, reason.to_string()));
+            }
+            TypeConstraints::Object { fields } | TypeConstraints::ArrayObject { fields, .. } => {
+                collect_schema_warnings(fields, &path, warnings);
+            }
+            TypeConstraints::Select { conditions, .. } => {
+                for condition_fields in conditions.all_fields() {
+                    let path = if conditions.insert_at_root { "" } else { &path };
+                    collect_schema_warnings(condition_fields, path, warnings);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The top level output key and dotted path of each redacted value.
+///
+/// The key is kept alongside the path as a key may itself contain dots.
+fn secret_paths(redacted: &BTreeMap<String, serde_json::Value>) -> Vec<(String, String)> {
+    let mut secrets = Vec::new();
+    for (key, value) in redacted {
+        let mut paths = Vec::new();
+        collect_secret_paths(key.clone(), value, &mut paths);
+        secrets.extend(paths.into_iter().map(|path| (key.clone(), path)));
+    }
+    secrets
+}
+
+/// Collects the dotted paths of the redacted values within the value.
+fn collect_secret_paths(path: String, value: &serde_json::Value, secrets: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(s) if s == REDACTED_VALUE => secrets.push(path),
+        serde_json::Value::Object(object) => {
+            for (key, value) in object {
+                collect_secret_paths(format!("{path}.{key}"), value, secrets);
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_secret_paths(format!("{path}[{index}]"), value, secrets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the fields of select conditions which are inserted at the root.
+fn collect_root_fields<'a>(
+    fields: &'a IndexMap<String, Field>,
+    root_fields: &mut Vec<&'a IndexMap<String, Field>>,
+) {
+    for field in fields.values() {
+        match &field.type_constraints {
+            TypeConstraints::Object { fields } | TypeConstraints::ArrayObject { fields, .. } => {
+                collect_root_fields(fields, root_fields);
+            }
+            TypeConstraints::Select { conditions, .. } => {
+                for condition_fields in conditions.all_fields() {
+                    if conditions.insert_at_root {
+                        root_fields.push(condition_fields);
+                    }
+                    collect_root_fields(condition_fields, root_fields);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects the keys of the object, and the objects nested within it,
+/// which none of the fields answer.
+fn collect_unknown_keys(
+    fields: &[&IndexMap<String, Field>],
+    tag: Option<&str>,
+    object: &serde_json::Map<String, serde_json::Value>,
+    path: &str,
+    errors: &mut Vec<(String, String)>,
+) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+
+    for key in object.keys() {
+        let known = Some(key.as_str()) == tag
+            || fields
+                .iter()
+                .flat_map(|fields| fields.iter())
+                .any(|(field_key, field)| {
+                    !field.is_section() && field.output_key(field_key) == key
+                });
+        if !known {
+            errors.push((join(key), "Key is not in the schema".to_string()));
+        }
+    }
+
+    for (key, field) in fields.iter().flat_map(|fields| fields.iter()) {
+        let value = match object.get(field.output_key(key)) {
+            Some(value) => value,
+            None => continue,
+        };
+
+        let path = join(key);
+        match (&field.type_constraints, value) {
+            (TypeConstraints::Object { fields }, serde_json::Value::Object(nested)) => {
+                collect_unknown_keys(&[fields], None, nested, &path, errors);
+            }
+            (TypeConstraints::ArrayObject { fields, .. }, serde_json::Value::Array(values)) => {
+                for (index, value) in values.iter().enumerate() {
+                    if let Some(nested) = value.as_object() {
+                        let path = format!("{path}[{index}]");
+                        collect_unknown_keys(&[fields], None, nested, &path, errors);
+                    }
+                }
+            }
+            (
+                TypeConstraints::Select {
+                    constraints,
+                    conditions,
+                },
+                value,
+            ) if !conditions.insert_at_root => {
+                let condition_fields = conditions.all_fields().collect::<Vec<_>>();
+                let values = value
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or(std::slice::from_ref(value));
+                for nested in values.iter().filter_map(|value| value.as_object()) {
+                    let tag = constraints.tag.as_deref();
+                    collect_unknown_keys(&condition_fields, tag, nested, &path, errors);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn validate_json<T, V>(value: &serde_json::Value, mut validator: V) -> Result<(), String>
+where
+    T: serde::de::DeserializeOwned,
+    V: Validator<T>,
+    V::Err: Display,
+{
+    let value = T::deserialize(value).map_err(|e| format!("Value {value} is not valid: {e}"))?;
+    validator.validate(&value).map_err(|e| e.to_string())
+}
+
+fn validate_array<T, V>(
+    value: &serde_json::Value,
+    constraints: &CollectionConstraints,
+    validator: V,
+) -> Result<(), String>
+where
+    T: serde::de::DeserializeOwned,
+    V: Validator<T> + Clone,
+    V::Err: Display,
+{
+    let values = value
+        .as_array()
+        .ok_or_else(|| format!("Value {value} is not an array"))?;
+    validate_item_count(values.len(), constraints)?;
+    constraints.validate_total_length(values)?;
+    constraints.validate_order(values)?;
+
+    for value in values {
+        validate_json::<T, _>(value, validator.clone())?;
+    }
+
+    Ok(())
+}
+
+fn validate_item_count(len: usize, constraints: &CollectionConstraints) -> Result<(), String> {
+    if len < constraints.min_items {
+        return Err(format!(
+            "This field requires a minimum of {} values to be provided.",
+            constraints.min_items
+        ));
+    }
+
+    if len > constraints.max_items {
+        return Err(format!(
+            "This field allows a maximum of {} values to be provided.",
+            constraints.max_items
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_object(
+    fields: &IndexMap<String, Field>,
+    object: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    for (key, field) in fields.iter().filter(|(_, field)| !field.is_section()) {
+        let value = object
+            .get(field.output_key(key))
+            .unwrap_or(&serde_json::Value::Null);
+        field
+            .validate_value(value)
+            .map_err(|e| format!("{key}: {e}"))?;
+    }
+    Ok(())
+}
+
+fn int_warning<T>(constraints: &IntConstraints<T>, value: &serde_json::Value) -> Vec<String>
+where
+    T: TraitIntBounds + Clone + Copy + Into<i128> + serde::de::DeserializeOwned,
+{
+    T::deserialize(value)
+        .ok()
+        .and_then(|value| constraints.warning(value))
+        .into_iter()
+        .collect()
+}
+
+/// A sample object for the fields, see [Schema::example].
+fn example_object(
+    fields: &IndexMap<String, Field>,
+    answers: &BTreeMap<String, serde_json::Value>,
+) -> serde_json::Value {
+    let object = fields
+        .iter()
+        .filter(|(_, field)| !field.is_section() && field.is_visible(answers))
+        .map(|(key, field)| {
+            let value = field.type_constraints.example(answers);
+            (field.output_key(key).to_string(), value)
+        })
+        .collect();
+    serde_json::Value::Object(object)
+}
+
+/// A sample array holding the minimum number of items, each made by
+/// `nth_item` from its index.
+///
+/// The items are reversed if they must be in decreasing order.
+fn example_array(
+    constraints: &CollectionConstraints,
+    nth_item: impl Fn(usize) -> serde_json::Value,
+) -> serde_json::Value {
+    let mut items = (0..constraints.min_items).map(nth_item).collect::<Vec<_>>();
+    if matches!(
+        constraints.monotonic,
+        Some(Monotonic::Decreasing | Monotonic::StrictlyDecreasing)
+    ) {
+        items.reverse();
+    }
+    serde_json::Value::Array(items)
+}
+
+/// The value of a one of bool field with the option at the index set.
+fn one_of_bool_value(options: &[String], index: usize, emit_name: bool) -> serde_json::Value {
+    if emit_name {
+        return serde_json::Value::from(options[index].clone());
+    }
+
+    let flags = options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| (option.clone(), serde_json::Value::from(i == index)))
+        .collect();
+    serde_json::Value::Object(flags)
+}
+
+/// The index of the option set in a one of bool value, in either form.
+fn chosen_option(options: &[String], value: &serde_json::Value) -> Option<usize> {
+    let name = match value {
+        serde_json::Value::String(name) => name.as_str(),
+        serde_json::Value::Object(flags) => flags
+            .iter()
+            .find(|(_, flag)| flag.as_bool() == Some(true))
+            .map(|(option, _)| option.as_str())?,
+        _ => return None,
+    };
+    options.iter().position(|option| option == name)
+}
+
+fn validate_one_of_bool(
+    options: &[String],
+    emit_name: bool,
+    value: &serde_json::Value,
+) -> Result<(), String> {
+    if emit_name {
+        return match value.as_str() {
+            Some(name) if options.iter().any(|option| option == name) => Ok(()),
+            _ => Err(format!(
+                "Value {value} is not one of the options: {}",
+                options.join(", ")
+            )),
+        };
+    }
+
+    let set = validate_flags(options, value)?;
+    if set != 1 {
+        return Err(format!("Exactly one option must be set, found {set}"));
+    }
+    Ok(())
+}
+
+/// Validates an object of option names to booleans, returning the
+/// number of options which are set.
+fn validate_flags(options: &[String], value: &serde_json::Value) -> Result<usize, String> {
+    let flags = value
+        .as_object()
+        .ok_or_else(|| format!("Value {value} is not an object"))?;
+    let mut set = 0;
+    for (option, flag) in flags {
+        if !options.contains(option) {
+            return Err(format!("{option:?} is not one of the options"));
+        }
+
+        match flag.as_bool() {
+            Some(true) => set += 1,
+            Some(false) => {}
+            None => return Err(format!("{option}: Value {flag} is not a boolean")),
+        }
+    }
+    Ok(set)
+}
+
+/// Validates a selected value, which is either one of the select items
+/// or the object produced by one of its conditions.
+fn validate_selected(
+    constraints: &SelectConstraints,
+    conditions: &Conditions,
+    value: &serde_json::Value,
+) -> Result<(), String> {
+    if let Some(tag) = constraints.tag.as_ref() {
+        return validate_tagged(constraints, conditions, tag, value);
+    }
+
+    if is_selectable(constraints, value) {
+        return Ok(());
+    }
+
+    if let Some(object) = value.as_object().filter(|_| !conditions.insert_at_root) {
+        let matches_condition = conditions
+            .all_fields()
+            .any(|fields| validate_object(fields, object).is_ok());
+
+        if matches_condition {
+            return Ok(());
+        }
+    }
+
+    Err(format!("Value {value} is not one of the selectable items"))
+}
+
+/// Is the value one of the select items.
+///
+/// Items taken from another field depend on the answers, which are not
+/// known here, and the items command is not run during validation, so
+/// with either set any value is accepted.
+fn is_selectable(constraints: &SelectConstraints, value: &serde_json::Value) -> bool {
+    constraints.items_from_field.is_some()
+        || constraints.items_command.is_some()
+        || constraints.items.contains(value)
+}
+
+/// Validates a selected value stored in an object under the tag,
+/// alongside the fields of the matching condition.
+fn validate_tagged(
+    constraints: &SelectConstraints,
+    conditions: &Conditions,
+    tag: &str,
+    value: &serde_json::Value,
+) -> Result<(), String> {
+    let mut object = value
+        .as_object()
+        .cloned()
+        .ok_or_else(|| format!("Value {value} is not an object"))?;
+    let selected = object
+        .remove(tag)
+        .ok_or_else(|| format!("Value {value} is missing the tag {tag:?}"))?;
+
+    if !is_selectable(constraints, &selected) {
+        return Err(format!(
+            "Value {selected} is not one of the selectable items"
+        ));
+    }
+
+    let condition = conditions
+        .if_conditions
+        .iter()
+        .find(|condition| condition.picked == selected)
+        .filter(|_| !conditions.insert_at_root);
+    match condition {
+        Some(condition) => validate_object(&condition.fields, &object),
+        None if object.is_empty() => Ok(()),
+        None => Err(format!("Value {value} has unexpected fields")),
+    }
+}
+
+/// Floats may be stored as strings, these are parsed back into numbers.
+fn parse_float_string(value: &serde_json::Value) -> serde_json::Value {
+    value
+        .as_str()
+        .and_then(|s| s.parse::<f64>().ok())
+        .map(serde_json::Value::from)
+        .unwrap_or_else(|| value.clone())
+}
+
+/// Renders the labels of the select items, marking the default item.
+fn select_labels(
+    items: &[serde_json::Value],
+    default_index: Option<usize>,
+    max_width: Option<usize>,
+) -> Vec<String> {
+    let default_style = Style::new().dim().for_stdout();
+    let default_suffix = "(default)";
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let label = display_value(item);
+            let is_default = Some(index) == default_index;
+            let label = match max_width {
+                Some(width) if is_default => {
+                    let width = width.saturating_sub(default_suffix.len() + 1);
+                    truncate_str(&label, width, "…").into_owned()
+                }
+                Some(width) => truncate_str(&label, width, "…").into_owned(),
+                None => label,
+            };
+            if is_default {
+                format!("{label} {}", default_style.apply_to(default_suffix))
+            } else {
+                label
+            }
+        })
+        .collect()
+}
+
+/// Prompts for an integer by picking it from the values of its range.
+fn prompt_int_menu(
+    field_name: &str,
+    items: &[i128],
+    radix: Radix,
+    settings: ValueSettings,
+) -> Result<serde_json::Value, Error> {
+    let default_index = settings
+        .default
+        .as_ref()
+        .and_then(|default| match default {
+            serde_json::Value::Number(number) => number
+                .as_i64()
+                .map(i128::from)
+                .or(number.as_u64().map(i128::from)),
+            _ => None,
+        })
+        .and_then(|default| items.iter().position(|&item| item == default));
+
+    let prompt = field_name.to_string();
+    let labels = items
+        .iter()
+        .map(|&item| radix.format(item))
+        .collect::<Vec<_>>();
+    let can_skip = settings.can_skip;
+    let selected = interact(field_name, settings.timeout, move || {
+        let theme = ColorfulTheme::default();
+        let mut select = Select::with_theme(&theme);
+        select
+            .with_prompt(prompt)
+            .default(default_index.unwrap_or(0))
+            .items(&labels);
+        if can_skip {
+            select.interact_opt()
+        } else {
+            select.interact().map(Some)
+        }
+    })?;
+
+    // Every value of a menu fits within an i64 or u64.
+    Ok(match selected.map(|index| items[index]) {
+        None => serde_json::Value::Null,
+        Some(value) => i64::try_from(value)
+            .map(serde_json::Value::from)
+            .unwrap_or_else(|_| serde_json::Value::from(value as u64)),
+    })
+}
+
+/// Replaces the `{path}` placeholders within the template by the values
+/// of the populated fields, or the given placeholder values.
+fn interpolate(
+    template: &str,
+    populated_fields: &BTreeMap<String, serde_json::Value>,
+    placeholders: &[(&str, &serde_json::Value)],
+) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..];
+        let end = match placeholder.find('}') {
+            Some(end) => end,
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        };
+
+        let key = &placeholder[..end];
+        let replacement = placeholders
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| *value)
+            .or_else(|| lookup_field(populated_fields, key));
+        match replacement {
+            Some(replacement) => rendered.push_str(&display_value(replacement)),
+            None => rendered.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &placeholder[end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn display_value(v: &serde_json::Value) -> String {
     match v {
         serde_json::Value::Null => "null".to_string(),
         serde_json::Value::Bool(b) => b.to_string(),
@@ -483,3 +3775,524 @@ fn display_value(v: &serde_json::Value) -> String {
         serde_json::Value::Object(o) => format!("{o:?}"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(value: serde_json::Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ordering_error(schema: &Schema) -> (String, String) {
+        match schema.ordered_fields() {
+            Err(Error::InvalidSchema { field, reason }) => (field, reason),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("the fields were ordered"),
+        }
+    }
+
+    #[test]
+    fn test_depends_on_order() {
+        let schema = schema(serde_json::json!({"fields": {
+            "a": {"type": "string", "depends_on": ["b"]},
+            "b": {"type": "string"},
+        }}));
+        let ordered = schema.ordered_fields().unwrap();
+        let keys = ordered
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_depends_on_cycle() {
+        let schema = schema(serde_json::json!({"fields": {
+            "a": {"type": "string", "depends_on": ["b"]},
+            "b": {"type": "string", "depends_on": ["a"]},
+        }}));
+        assert_eq!(
+            ordering_error(&schema),
+            (
+                "a".to_string(),
+                "depends_on forms a cycle: a -> b -> a".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_depends_on_itself() {
+        let schema = schema(serde_json::json!({"fields": {
+            "a": {"type": "string", "depends_on": ["a"]},
+        }}));
+        assert_eq!(
+            ordering_error(&schema),
+            (
+                "a".to_string(),
+                "depends_on forms a cycle: a -> a".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_depends_on_unknown_field() {
+        let schema = schema(serde_json::json!({"fields": {
+            "a": {"type": "string", "depends_on": ["missing"]},
+        }}));
+        assert_eq!(
+            ordering_error(&schema),
+            (
+                "a".to_string(),
+                "depends_on refers to an unknown field \"missing\"".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_confirm_mismatch_then_match() {
+        let field: Field = serde_json::from_value(serde_json::json!({
+            "type": "string",
+            "confirm": true,
+        }))
+        .unwrap();
+
+        let mut answers = vec![
+            "a@example.com",
+            "b@example.com",
+            "a@example.com",
+            "a@example.com",
+        ]
+        .into_iter()
+        .map(serde_json::Value::from);
+        let mut prompts = Vec::new();
+        let value = field
+            .read_accepted("Email", "email", &ValueSettings::default(), |name, _| {
+                prompts.push(name.to_string());
+                Ok((answers.next().unwrap(), false))
+            })
+            .unwrap();
+
+        assert_eq!(value, serde_json::json!("a@example.com"));
+        assert_eq!(
+            prompts,
+            vec!["Email", "Confirm Email", "Email", "Confirm Email"]
+        );
+    }
+
+    #[test]
+    fn test_select_without_items() {
+        let schema = schema(serde_json::json!({"fields": {
+            "required": {"type": "select", "items": []},
+            "optional": {"type": "select", "items": [], "can_skip": true},
+            "hosts": {"type": "string[]"},
+            "primary": {"type": "select", "items_from_field": "hosts"},
+        }}));
+        assert_eq!(
+            schema.validate().unwrap_err(),
+            vec![(
+                "required".to_string(),
+                "There are no items to select".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_skipped_select_insert_at_root() {
+        let field = |insert_at_root| -> Field {
+            serde_json::from_value(serde_json::json!({
+                "type": "select",
+                "items": ["us-east", "eu-west"],
+                "can_skip": true,
+                "then": {
+                    "insert_at_root": insert_at_root,
+                    "on_skip": {"custom_region": {"type": "object", "fields": {}}},
+                },
+            }))
+            .unwrap()
+        };
+        let options = PromptOptions {
+            quiet: true,
+            ..Default::default()
+        };
+        let conditions = |field: &Field| match &field.type_constraints {
+            TypeConstraints::Select { conditions, .. } => conditions.clone(),
+            _ => unreachable!(),
+        };
+
+        let mut populated_fields = BTreeMap::new();
+        let nested = check_skip_conditions(
+            "region",
+            &conditions(&field(false)),
+            &options,
+            &mut false,
+            &mut populated_fields,
+        )
+        .unwrap();
+        assert_eq!(nested, Some(serde_json::json!({"custom_region": {}})));
+        assert!(populated_fields.is_empty());
+
+        let at_root = check_skip_conditions(
+            "region",
+            &conditions(&field(true)),
+            &options,
+            &mut false,
+            &mut populated_fields,
+        )
+        .unwrap();
+        assert_eq!(at_root, None);
+        assert_eq!(populated_fields["custom_region"], serde_json::json!({}));
+
+        let skip_remaining = check_skip_conditions(
+            "region",
+            &conditions(&field(true)),
+            &options,
+            &mut true,
+            &mut BTreeMap::new(),
+        )
+        .unwrap();
+        assert_eq!(skip_remaining, None);
+    }
+
+    #[test]
+    fn test_immutable_field_in_condition() {
+        let conditions: Conditions = serde_json::from_value(serde_json::json!({
+            "insert_at_root": true,
+            "if": [{
+                "picked": "rename",
+                "fields": {"name": {"type": "string", "immutable": true}},
+            }],
+        }))
+        .unwrap();
+        let options = PromptOptions {
+            quiet: true,
+            ..Default::default()
+        };
+
+        let mut populated_fields = BTreeMap::from([
+            ("name".to_string(), serde_json::json!("core")),
+            ("action".to_string(), serde_json::json!("rename")),
+        ]);
+        let value = check_conditions(
+            "action",
+            &conditions,
+            |condition| condition.picked == "rename",
+            &options,
+            &mut false,
+            &mut populated_fields,
+        )
+        .unwrap();
+
+        assert_eq!(value, None);
+        assert_eq!(populated_fields["name"], serde_json::json!("core"));
+    }
+
+    #[test]
+    fn test_blank_array_item() {
+        let constraints: CollectionConstraints =
+            serde_json::from_value(serde_json::json!({"min_items": 2})).unwrap();
+
+        assert!(matches!(
+            blank_item(0, &constraints, true),
+            BlankItem::ConfirmSkip(_)
+        ));
+        assert_eq!(
+            blank_item(0, &constraints, false),
+            BlankItem::MoreRequired(
+                "2 more values are required, this field needs at least 2.".to_string()
+            )
+        );
+        assert_eq!(
+            blank_item(1, &constraints, true),
+            BlankItem::MoreRequired(
+                "1 more value is required, this field needs at least 2.".to_string()
+            )
+        );
+        assert_eq!(blank_item(2, &constraints, false), BlankItem::Finish);
+        assert_eq!(blank_item(3, &constraints, true), BlankItem::Finish);
+    }
+
+    #[test]
+    fn test_nested_required_pointers() {
+        let schema = schema(serde_json::json!({
+            "required": ["/db/host", "/users/0/name"],
+            "fields": {
+                "db": {"type": "object", "fields": {
+                    "host": {"type": "string", "can_skip": true},
+                    "port": {"type": "u16", "can_skip": true},
+                }},
+                "users": {"type": "object[]", "can_skip": true, "fields": {
+                    "name": {"type": "string", "can_skip": true},
+                }},
+            },
+        }));
+        assert!(schema.validate().is_ok());
+
+        let mut answers = BTreeMap::from([
+            (
+                "db".to_string(),
+                serde_json::json!({"host": null, "port": 5432}),
+            ),
+            ("users".to_string(), serde_json::json!([{"name": "alice"}])),
+        ]);
+        assert_eq!(schema.missing_required(&answers), Some("/db/host"));
+        assert_eq!(
+            schema.validate_answers_with(&answers, false).unwrap_err(),
+            vec![("/db/host".to_string(), "A value is required".to_string())]
+        );
+
+        answers.insert("db".to_string(), serde_json::json!({"host": "localhost"}));
+        answers.insert("users".to_string(), serde_json::json!([]));
+        assert_eq!(schema.missing_required(&answers), Some("/users/0/name"));
+
+        answers.insert("users".to_string(), serde_json::json!([{"name": "alice"}]));
+        assert_eq!(schema.missing_required(&answers), None);
+        assert!(schema.validate_answers_with(&answers, false).is_ok());
+
+        let invalid = Schema {
+            required: vec!["db/host".to_string()],
+            ..schema
+        };
+        assert_eq!(invalid.validate().unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn test_example_satisfies_schema() {
+        let schema = schema(serde_json::json!({"fields": {
+            "port": {
+                "type": "u16",
+                "min": 1024,
+                "depends_on": ["mode"],
+                "show_if": {"field": "mode", "equals": "server"},
+            },
+            "mode": {"type": "select", "items": ["server", "client"]},
+            "users": {"type": "object[]", "min_items": 3, "unique_by": ["username"], "fields": {
+                "username": {"type": "string", "min_length": 3, "max_length": 8},
+                "role": {"type": "select", "items": ["admin", "member"]},
+            }},
+            "ports": {
+                "type": "u16[]",
+                "min": 8000,
+                "min_items": 3,
+                "monotonic": "strictly_decreasing",
+            },
+            "weights": {
+                "type": "f64[]",
+                "max": 1.5,
+                "exclusive_max": true,
+                "min_items": 4,
+                "monotonic": "strictly_increasing",
+            },
+        }}));
+
+        let example = schema.example();
+        assert_eq!(example["port"], serde_json::json!(1024));
+        assert_eq!(
+            example["users"],
+            serde_json::json!([
+                {"username": "example", "role": "admin"},
+                {"username": "example1", "role": "admin"},
+                {"username": "example2", "role": "admin"},
+            ])
+        );
+        assert_eq!(example["ports"], serde_json::json!([8002, 8001, 8000]));
+        assert_eq!(
+            example["weights"],
+            serde_json::json!([0.0, 1.0, 1.25, 1.3333333333333333])
+        );
+
+        let answers = serde_json::from_value(example).unwrap();
+        assert!(schema.validate_answers(&answers).is_ok());
+    }
+
+    #[test]
+    fn test_items_command_select_accepts_any_value() {
+        let schema = schema(serde_json::json!({"fields": {
+            "branch": {"type": "select", "items": ["main"], "items_command": "exit 1"},
+            "region": {"type": "select", "items": ["us-east"]},
+        }}));
+
+        let answers = BTreeMap::from([
+            ("branch".to_string(), serde_json::json!("feature")),
+            ("region".to_string(), serde_json::json!("us-east")),
+        ]);
+        assert!(schema.validate_answers(&answers).is_ok());
+
+        let answers = BTreeMap::from([
+            ("branch".to_string(), serde_json::json!("main")),
+            ("region".to_string(), serde_json::json!("eu-west")),
+        ]);
+        assert_eq!(
+            schema.validate_answers(&answers).unwrap_err(),
+            vec![(
+                "region".to_string(),
+                "Value \"eu-west\" is not one of the selectable items".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_command_only_runs_at_prompt() {
+        let field: Field = serde_json::from_value(serde_json::json!({
+            "type": "string",
+            "validate_command": "exit 1",
+            "max_attempts": 1,
+        }))
+        .unwrap();
+
+        assert!(field.validate_value(&serde_json::json!("main")).is_ok());
+
+        let mut reads = 0;
+        let result = field.read_accepted("Branch", "branch", &ValueSettings::default(), |_, _| {
+            reads += 1;
+            Ok((serde_json::json!("main"), false))
+        });
+        assert!(result.is_err());
+        assert_eq!(reads, 1);
+    }
+
+    #[test]
+    fn test_prompt_into_document() {
+        let schema = schema(serde_json::json!({"fields": {
+            "settings": {"type": "object", "fields": {}},
+        }}));
+        let options = PromptOptions {
+            quiet: true,
+            ..Default::default()
+        };
+
+        let mut doc = serde_json::json!({"config": {"keep": 1}, "name": "app"});
+        schema
+            .prompt_into_document(&options, &mut doc, "/config/app")
+            .unwrap();
+        assert_eq!(
+            doc,
+            serde_json::json!({
+                "config": {"keep": 1, "app": {"settings": {}}},
+                "name": "app",
+            })
+        );
+
+        let error = schema
+            .prompt_into_document(&options, &mut doc, "/name/app")
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidPointer { .. }));
+    }
+
+    #[test]
+    fn test_tri_bool() {
+        let field: Field = serde_json::from_value(serde_json::json!({
+            "type": "tribool",
+            "can_skip": true,
+        }))
+        .unwrap();
+
+        for value in TRI_BOOL_VALUES.iter() {
+            assert!(field.validate_value(value).is_ok());
+        }
+        assert!(field.validate_value(&serde_json::json!("yes")).is_err());
+    }
+
+    #[test]
+    fn test_omit_field() {
+        let omitted_result = || {
+            Err(Error::FieldOmitted {
+                field: "Host".to_string(),
+            })
+        };
+
+        let mut omitted = Vec::new();
+        let value = omit_field(omitted_result(), "db", true, &mut omitted).unwrap();
+        assert_eq!(value, serde_json::Value::Null);
+        let value = omit_field(Ok(serde_json::json!(5432)), "port", true, &mut omitted).unwrap();
+        assert_eq!(value, serde_json::json!(5432));
+        assert_eq!(omitted, vec!["db"]);
+
+        let mut omitted = Vec::new();
+        let error = omit_field(omitted_result(), "db", false, &mut omitted).unwrap_err();
+        assert!(matches!(error, Error::RequiredFieldSkipped { field } if field == "db"));
+        assert!(omitted.is_empty());
+    }
+
+    #[test]
+    fn test_secret_paths() {
+        let schema = schema(serde_json::json!({"fields": {
+            "db.password": {"type": "string", "sensitive": true},
+            "users": {"type": "object[]", "fields": {
+                "name": {"type": "string"},
+                "token": {"type": "string", "sensitive": true},
+            }},
+            "region": {"type": "string"},
+        }}));
+        let answers = BTreeMap::from([
+            ("db.password".to_string(), serde_json::json!("hunter2")),
+            (
+                "users".to_string(),
+                serde_json::json!([{"name": "alice", "token": "a"}, {"name": "bob", "token": "b"}]),
+            ),
+            ("region".to_string(), serde_json::json!("eu")),
+        ]);
+
+        let secrets = secret_paths(&schema.redact(&answers));
+        let expected = [
+            ("db.password", "db.password"),
+            ("users", "users[0].token"),
+            ("users", "users[1].token"),
+        ];
+        assert_eq!(
+            secrets,
+            expected.map(|(key, path)| (key.to_string(), path.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_wrap_line() {
+        assert_eq!(
+            wrap_line("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(
+            wrap_line("  - an indented item", 12),
+            vec!["  - an", "indented", "item"]
+        );
+        assert_eq!(wrap_line("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(
+            wrap_line("see 日本語のテキスト", 8),
+            vec!["see", "日本語の", "テキスト"]
+        );
+        for line in wrap_line("see 日本語のテキスト", 8) {
+            assert!(measure_text_width(&line) <= 8);
+        }
+        assert_eq!(wrap_line("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_select_duplicate_item() {
+        let schema = schema(serde_json::json!({"fields": {
+            "region": {"type": "select", "items": ["us-east", "eu-west", "us-east"]},
+            "port": {"type": "select", "items": [80, 443]},
+        }}));
+        assert_eq!(
+            schema.validate().unwrap_err(),
+            vec![(
+                "region".to_string(),
+                "The item \"us-east\" is listed more than once".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unique_by_duplicate_username() {
+        let unique_by = vec!["username".to_string()];
+        let values = vec![
+            serde_json::json!({"username": "alice", "role": "admin"}),
+            serde_json::json!({"username": "bob", "role": "member"}),
+            serde_json::json!({"username": "alice", "role": "member"}),
+        ];
+
+        assert_eq!(duplicate_of(&values[..2], &values[2], &unique_by), Some(0));
+        let error = validate_unique_by(&values, &unique_by).unwrap_err();
+        assert_eq!(error, "Item 3 has the same username \"alice\" as item 1");
+        assert!(validate_unique_by(&values[..2], &unique_by).is_ok());
+    }
+}
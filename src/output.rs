@@ -0,0 +1,521 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use indexmap::IndexMap;
+
+use crate::{Error, Field, TypeConstraints};
+
+/// The text shown in place of a sensitive value.
+pub static REDACTED_VALUE: &str = "••••••";
+
+/// Flattens nested objects in the answers into keys joined by the separator.
+///
+/// For example `{"db": {"host": "..."}}` becomes `{"db.host": "..."}` with
+/// a separator of `.`. Arrays and empty objects are left intact.
+pub fn flatten(
+    answers: &BTreeMap<String, serde_json::Value>,
+    separator: &str,
+) -> BTreeMap<String, serde_json::Value> {
+    let mut flattened = BTreeMap::new();
+    for (key, value) in answers {
+        flatten_value(key.clone(), value, separator, &mut flattened);
+    }
+    flattened
+}
+
+fn flatten_value(
+    key: String,
+    value: &serde_json::Value,
+    separator: &str,
+    flattened: &mut BTreeMap<String, serde_json::Value>,
+) {
+    match value {
+        serde_json::Value::Object(object) if !object.is_empty() => {
+            for (nested_key, nested_value) in object {
+                let key = format!("{key}{separator}{nested_key}");
+                flatten_value(key, nested_value, separator, flattened);
+            }
+        }
+        _ => {
+            flattened.insert(key, value.clone());
+        }
+    }
+}
+
+/// Expands keys joined by the separator back into nested objects.
+///
+/// This is the inverse of [flatten] for keys which do not conflict, if a
+/// key is both a value and the parent of other keys the later key wins.
+pub fn unflatten(
+    flattened: &BTreeMap<String, serde_json::Value>,
+    separator: &str,
+) -> BTreeMap<String, serde_json::Value> {
+    let mut root = serde_json::Map::new();
+    for (key, value) in flattened {
+        let mut parts = key.split(separator).peekable();
+        let mut object = &mut root;
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                object.insert(part.to_string(), value.clone());
+                break;
+            }
+
+            let entry = object
+                .entry(part.to_string())
+                .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+            if !entry.is_object() {
+                *entry = serde_json::Value::Object(serde_json::Map::new());
+            }
+            object = entry.as_object_mut().unwrap();
+        }
+    }
+    root.into_iter().collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How [merge_answers] resolves a key present in both sets of answers.
+pub enum MergeStrategy {
+    /// The overlay's value replaces the base's value.
+    OverlayWins,
+    /// The base's value is kept.
+    BaseWins,
+    /// Objects are merged key by key and arrays are concatenated, any
+    /// other value is replaced by the overlay's value.
+    Deep,
+}
+
+/// Merges the overlay answers into the base answers.
+///
+/// A `null` value is treated as unanswered, so it never replaces a value
+/// from the other side, whichever strategy is used. Keys only present on
+/// one side are always kept.
+pub fn merge_answers(
+    base: &BTreeMap<String, serde_json::Value>,
+    overlay: &BTreeMap<String, serde_json::Value>,
+    strategy: MergeStrategy,
+) -> BTreeMap<String, serde_json::Value> {
+    let mut merged = base.clone();
+    for (key, value) in overlay {
+        match merged.get_mut(key) {
+            Some(existing) => merge_value(existing, value, strategy),
+            None => {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    merged
+}
+
+fn merge_value(base: &mut serde_json::Value, overlay: &serde_json::Value, strategy: MergeStrategy) {
+    if overlay.is_null() {
+        return;
+    } else if base.is_null() {
+        *base = overlay.clone();
+        return;
+    }
+
+    match (strategy, base, overlay) {
+        (MergeStrategy::BaseWins, _, _) => {}
+        (
+            MergeStrategy::Deep,
+            serde_json::Value::Object(base),
+            serde_json::Value::Object(overlay),
+        ) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_value(existing, value, strategy),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (
+            MergeStrategy::Deep,
+            serde_json::Value::Array(base),
+            serde_json::Value::Array(overlay),
+        ) => {
+            base.extend(overlay.iter().cloned());
+        }
+        (_, base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// Checks the JSON pointer only traverses objects within the document.
+pub(crate) fn check_pointer(doc: &serde_json::Value, pointer: &str) -> Result<(), Error> {
+    let mut value = doc;
+    for token in pointer_tokens(pointer)? {
+        match value {
+            serde_json::Value::Object(object) => match object.get(&token) {
+                Some(nested) => value = nested,
+                None => return Ok(()),
+            },
+            serde_json::Value::Null => return Ok(()),
+            _ => return Err(not_an_object(pointer, &token)),
+        }
+    }
+    match value {
+        serde_json::Value::Object(_) | serde_json::Value::Null => Ok(()),
+        _ => Err(Error::InvalidPointer {
+            pointer: pointer.to_string(),
+            reason: "the target is not an object".to_string(),
+        }),
+    }
+}
+
+/// Inserts the answers into the object at the JSON pointer within the document.
+///
+/// Missing or `null` objects along the way are created, keys already in
+/// the target object which were not answered are kept.
+pub(crate) fn insert_at_pointer(
+    doc: &mut serde_json::Value,
+    pointer: &str,
+    answers: BTreeMap<String, serde_json::Value>,
+) -> Result<(), Error> {
+    check_pointer(doc, pointer)?;
+
+    let mut value = doc;
+    for token in pointer_tokens(pointer)? {
+        if value.is_null() {
+            *value = serde_json::Value::Object(serde_json::Map::new());
+        }
+        value = match value {
+            serde_json::Value::Object(object) => {
+                object.entry(token).or_insert(serde_json::Value::Null)
+            }
+            _ => return Err(not_an_object(pointer, &token)),
+        };
+    }
+    if value.is_null() {
+        *value = serde_json::Value::Object(serde_json::Map::new());
+    }
+    if let serde_json::Value::Object(object) = value {
+        object.extend(answers);
+    }
+    Ok(())
+}
+
+pub(crate) fn pointer_tokens(pointer: &str) -> Result<Vec<String>, Error> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    match pointer.strip_prefix('/') {
+        Some(pointer) => Ok(pointer
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect()),
+        None => Err(Error::InvalidPointer {
+            pointer: pointer.to_string(),
+            reason: "a pointer must be empty or start with '/'".to_string(),
+        }),
+    }
+}
+
+fn not_an_object(pointer: &str, token: &str) -> Error {
+    Error::InvalidPointer {
+        pointer: pointer.to_string(),
+        reason: format!("the parent of {token:?} is not an object"),
+    }
+}
+
+/// Writes the answers to the file keyed by the JSON pointer of each
+/// value, i.e. `/db/host`.
+///
+/// Files ending in `.yaml` or `.yml` are written as YAML if the `yaml`
+/// feature is enabled, any other file is written as JSON.
+pub(crate) fn write_recording(
+    answers: &BTreeMap<String, serde_json::Value>,
+    path: &Path,
+) -> Result<(), Error> {
+    let mut transcript = BTreeMap::new();
+    for (key, value) in answers {
+        record_value(format!("/{}", escape_token(key)), value, &mut transcript);
+    }
+    let contents = match is_yaml(path) {
+        #[cfg(feature = "yaml")]
+        true => serde_yaml::to_string(&transcript).map_err(|e| Error::InvalidRecording {
+            reason: e.to_string(),
+        })?,
+        _ => serde_json::to_string_pretty(&transcript).map_err(Error::Deserialize)?,
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Reads the answers written by [write_recording].
+pub(crate) fn read_recording(path: &Path) -> Result<BTreeMap<String, serde_json::Value>, Error> {
+    let contents = fs::read_to_string(path)?;
+    let transcript: BTreeMap<String, serde_json::Value> = match is_yaml(path) {
+        #[cfg(feature = "yaml")]
+        true => serde_yaml::from_str(&contents).map_err(|e| Error::InvalidRecording {
+            reason: e.to_string(),
+        })?,
+        _ => serde_json::from_str(&contents).map_err(|e| Error::InvalidRecording {
+            reason: e.to_string(),
+        })?,
+    };
+
+    let mut root = serde_json::Map::new();
+    for (pointer, value) in transcript {
+        let tokens = pointer_tokens(&pointer).map_err(|e| Error::InvalidRecording {
+            reason: e.to_string(),
+        })?;
+        let (last, parents) = match tokens.split_last() {
+            Some(split) => split,
+            None => {
+                return Err(Error::InvalidRecording {
+                    reason: "a value is recorded at the root".to_string(),
+                })
+            }
+        };
+
+        let mut object = &mut root;
+        for token in parents {
+            let entry = object
+                .entry(token.clone())
+                .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+            object = match entry {
+                serde_json::Value::Object(nested) => nested,
+                _ => {
+                    return Err(Error::InvalidRecording {
+                        reason: format!("the parent of {pointer:?} is not an object"),
+                    })
+                }
+            };
+        }
+        object.insert(last.clone(), value);
+    }
+    Ok(root.into_iter().collect())
+}
+
+/// Records the value under its pointer, nested objects are recorded key
+/// by key while arrays and empty objects are kept whole.
+fn record_value(
+    pointer: String,
+    value: &serde_json::Value,
+    transcript: &mut BTreeMap<String, serde_json::Value>,
+) {
+    match value {
+        serde_json::Value::Object(object) if !object.is_empty() => {
+            for (key, nested) in object {
+                record_value(
+                    format!("{pointer}/{}", escape_token(key)),
+                    nested,
+                    transcript,
+                );
+            }
+        }
+        _ => {
+            transcript.insert(pointer, value.clone());
+        }
+    }
+}
+
+/// Escapes a key for use as a JSON pointer token, the inverse of [pointer_tokens].
+fn escape_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn is_yaml(path: &Path) -> bool {
+    cfg!(feature = "yaml")
+        && path
+            .extension()
+            .is_some_and(|extension| extension == "yaml" || extension == "yml")
+}
+
+#[cfg(feature = "yaml")]
+/// Serializes the answers into a YAML document.
+///
+/// Nested objects become mappings and arrays become sequences. Skipped
+/// fields are only written as `null` if `include_null` is set.
+pub fn to_yaml(
+    answers: &BTreeMap<String, serde_json::Value>,
+    include_null: bool,
+) -> Result<String, serde_yaml::Error> {
+    let mut answers = answers.clone();
+    if !include_null {
+        answers.retain(|_, value| !value.is_null());
+        answers.values_mut().for_each(remove_nulls);
+    }
+    serde_yaml::to_string(&answers)
+}
+
+#[cfg(feature = "yaml")]
+fn remove_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.retain(|_, value| !value.is_null());
+            object.values_mut().for_each(remove_nulls);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+/// Replaces the values of sensitive fields within the object.
+///
+/// Fields which select conditions insert at the root are added to
+/// `root_fields` so they can be redacted once the root is available.
+pub(crate) fn redact_fields<'a>(
+    fields: &'a IndexMap<String, Field>,
+    object: &mut serde_json::Map<String, serde_json::Value>,
+    root_fields: &mut Vec<&'a IndexMap<String, Field>>,
+) {
+    for (key, field) in fields {
+        if let Some(value) = object.get_mut(field.output_key(key)) {
+            redact_value(field, value, root_fields);
+        }
+    }
+}
+
+/// Replaces the field's value, or the values of its sensitive nested fields.
+pub(crate) fn redact_value<'a>(
+    field: &'a Field,
+    value: &mut serde_json::Value,
+    root_fields: &mut Vec<&'a IndexMap<String, Field>>,
+) {
+    if value.is_null() {
+        return;
+    }
+
+    if field.sensitive {
+        *value = serde_json::Value::from(REDACTED_VALUE);
+        return;
+    }
+
+    match &field.type_constraints {
+        TypeConstraints::Object { fields } => {
+            if let Some(nested) = value.as_object_mut() {
+                redact_fields(fields, nested, root_fields);
+            }
+        }
+        TypeConstraints::ArrayObject { fields, .. } => {
+            let values = value.as_array_mut().into_iter().flatten();
+            for nested in values.filter_map(|value| value.as_object_mut()) {
+                redact_fields(fields, nested, root_fields);
+            }
+        }
+        TypeConstraints::Select { conditions, .. } => {
+            for condition_fields in conditions.all_fields() {
+                if conditions.insert_at_root {
+                    root_fields.push(condition_fields);
+                    continue;
+                }
+
+                let values = match &mut *value {
+                    serde_json::Value::Array(values) => values.iter_mut().collect(),
+                    value => vec![value],
+                };
+                for nested in values.into_iter().filter_map(|value| value.as_object_mut()) {
+                    redact_fields(condition_fields, nested, root_fields);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(value: serde_json::Value) -> BTreeMap<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn merge(strategy: MergeStrategy) -> BTreeMap<String, serde_json::Value> {
+        let base = answers(serde_json::json!({
+            "db": {"host": "localhost", "port": 5432, "user": null},
+            "tags": ["a", "b"],
+            "name": "base",
+            "token": "secret",
+            "region": null,
+            "only_base": 1,
+        }));
+        let overlay = answers(serde_json::json!({
+            "db": {"port": 6543, "user": "admin"},
+            "tags": ["c"],
+            "name": "overlay",
+            "token": null,
+            "region": "eu",
+            "only_overlay": 2,
+        }));
+        merge_answers(&base, &overlay, strategy)
+    }
+
+    #[test]
+    fn test_merge_overlay_wins() {
+        let expected = answers(serde_json::json!({
+            "db": {"port": 6543, "user": "admin"},
+            "tags": ["c"],
+            "name": "overlay",
+            "token": "secret",
+            "region": "eu",
+            "only_base": 1,
+            "only_overlay": 2,
+        }));
+        assert_eq!(merge(MergeStrategy::OverlayWins), expected);
+    }
+
+    #[test]
+    fn test_merge_base_wins() {
+        let expected = answers(serde_json::json!({
+            "db": {"host": "localhost", "port": 5432, "user": null},
+            "tags": ["a", "b"],
+            "name": "base",
+            "token": "secret",
+            "region": "eu",
+            "only_base": 1,
+            "only_overlay": 2,
+        }));
+        assert_eq!(merge(MergeStrategy::BaseWins), expected);
+    }
+
+    #[test]
+    fn test_merge_deep() {
+        let expected = answers(serde_json::json!({
+            "db": {"host": "localhost", "port": 6543, "user": "admin"},
+            "tags": ["a", "b", "c"],
+            "name": "overlay",
+            "token": "secret",
+            "region": "eu",
+            "only_base": 1,
+            "only_overlay": 2,
+        }));
+        assert_eq!(merge(MergeStrategy::Deep), expected);
+    }
+
+    #[test]
+    fn test_recording_round_trip() {
+        let recorded = answers(serde_json::json!({
+            "db.host": "localhost",
+            "db": {"host": "db.internal", "a/b": 1, "~tilde": 2, "empty": {}},
+            "hosts": ["a.example.com", "b.example.com"],
+            "skipped": null,
+        }));
+        let path =
+            std::env::temp_dir().join(format!("promptea-recording-{}.json", std::process::id()));
+
+        write_recording(&recorded, &path).unwrap();
+        let transcript: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let replayed = read_recording(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            transcript,
+            serde_json::json!({
+                "/db.host": "localhost",
+                "/db/host": "db.internal",
+                "/db/a~1b": 1,
+                "/db/~0tilde": 2,
+                "/db/empty": {},
+                "/hosts": ["a.example.com", "b.example.com"],
+                "/skipped": null,
+            })
+        );
+        assert_eq!(replayed.unwrap(), recorded);
+    }
+}
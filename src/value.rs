@@ -1,9 +1,62 @@
 use std::fmt::{Debug, Display};
 use std::io;
+use std::num::{IntErrorKind, ParseIntError};
 use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
-use dialoguer::theme::ColorfulTheme;
-use dialoguer::{Confirm, Input, Validator};
+use console::{Style, Term};
+use dialoguer::theme::{ColorfulTheme, Theme};
+use dialoguer::{Confirm, Editor, Input, Validator};
+
+use crate::constraints::check_decimal_places;
+use crate::{CollectionConstraints, Error};
+
+/// The input which skips the current field and every field after it.
+pub static SKIP_REMAINING_INPUT: &str = ":skiprest";
+
+/// The input which leaves a required field unanswered and moves on.
+///
+/// Only [Schema::prompt_draft](crate::Schema::prompt_draft) accepts this,
+/// for a field which can be skipped it is the same as a blank input and
+/// anywhere else it is rejected as invalid.
+pub static OMIT_INPUT: &str = ":omit";
+
+/// The input which explicitly sets a value to `null`, if the field allows it.
+///
+/// This takes precedence over the field's own parsing, so a string field
+/// which allows `null` cannot be given this literal text.
+pub static NULL_INPUT: &str = ":null";
+
+#[derive(Debug, Clone, Default)]
+/// The settings used when prompting the user for a single value.
+pub struct ValueSettings {
+    /// Can the value be skipped/left blank.
+    pub can_skip: bool,
+    /// The maximum number of invalid values the user can enter in a row
+    /// before [Error::TooManyAttempts] is returned.
+    pub max_attempts: Option<usize>,
+    /// How long to wait for the user to answer before
+    /// [Error::Timeout] is returned.
+    pub timeout: Option<Duration>,
+    /// The value shown as the initial text or selected by default.
+    pub default: Option<serde_json::Value>,
+    /// Can [NULL_INPUT] be entered to set the value to `null`.
+    pub allow_null: bool,
+    /// Should the default be shown beside the prompt and used for blank
+    /// input, rather than as the initial text.
+    ///
+    /// This only applies to values which cannot be skipped, as blank
+    /// input skips the others.
+    pub empty_accepts_default: bool,
+    /// The maximum number of decimal places which can be typed, checked
+    /// before the input is parsed so no digits are lost to rounding.
+    pub max_decimal_places: Option<u8>,
+    /// Can [OMIT_INPUT] be entered to leave a value which cannot be
+    /// skipped unanswered, returning [Error::FieldOmitted].
+    pub allow_omit: bool,
+}
 
 /// A value which can prompt a user for a value.
 ///
@@ -19,8 +72,9 @@ where
     fn prompt(
         field_name: impl Display,
         validator: Option<V>,
-        can_skip: bool,
-    ) -> io::Result<Option<Self>>;
+        settings: ValueSettings,
+        skip_remaining: &mut bool,
+    ) -> Result<Option<Self>, Error>;
 }
 
 impl<'a, V> PromptValue<'a, V> for String
@@ -31,30 +85,27 @@ where
     fn prompt(
         field_name: impl Display,
         mut validator: Option<V>,
-        can_skip: bool,
-    ) -> io::Result<Option<Self>> {
-        Input::with_theme(&ColorfulTheme::default())
-            .with_prompt(field_name.to_string())
-            .allow_empty(can_skip)
-            .validate_with(|input: &String| -> Result<(), String> {
-                if can_skip && input.is_empty() {
-                    return Ok(());
-                }
+        settings: ValueSettings,
+        skip_remaining: &mut bool,
+    ) -> Result<Option<Self>, Error> {
+        let input = read_text(field_name, &settings, |input| {
+            if let Some(validator) = validator.as_mut() {
+                validator.validate(input).map_err(|e| e.to_string())
+            } else {
+                Ok(())
+            }
+        })?;
 
-                if let Some(validator) = validator.as_mut() {
-                    validator.validate(input).map_err(|e| e.to_string())
-                } else {
-                    Ok(())
-                }
-            })
-            .interact_text()
-            .map(|input| {
-                if can_skip && input.is_empty() {
-                    None
-                } else {
-                    Some(input)
-                }
-            })
+        if input == SKIP_REMAINING_INPUT {
+            *skip_remaining = true;
+            Ok(None)
+        } else if (settings.can_skip && input.is_empty())
+            || (settings.allow_null && input == NULL_INPUT)
+        {
+            Ok(None)
+        } else {
+            Ok(Some(input))
+        }
     }
 }
 
@@ -66,15 +117,429 @@ where
     fn prompt(
         field_name: impl Display,
         _validator: Option<V>,
-        _can_skip: bool,
-    ) -> io::Result<Option<Self>> {
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(field_name.to_string())
-            .default(false)
-            .interact_opt()
+        settings: ValueSettings,
+        _skip_remaining: &mut bool,
+    ) -> Result<Option<Self>, Error> {
+        let field_name = field_name.to_string();
+        let prompt = field_name.clone();
+        let default = settings
+            .default
+            .as_ref()
+            .and_then(|default| default.as_bool())
+            .unwrap_or(false);
+        interact(&field_name, settings.timeout, move || {
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .default(default)
+                .interact_opt()
+        })
+    }
+}
+
+/// Runs a terminal interaction, giving up once the timeout elapses.
+///
+/// When a timeout is set the interaction runs on its own thread. The thread
+/// cannot be cancelled and keeps reading the next key pressed, so a timeout
+/// must end the session rather than prompt again. The terminal settings and
+/// cursor are restored before [Error::Timeout] is returned.
+pub(crate) fn interact<T, F>(
+    field_name: &str,
+    timeout: Option<Duration>,
+    interaction: F,
+) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    let timeout = match timeout {
+        None => return Ok(interaction()?),
+        Some(timeout) => timeout,
+    };
+
+    let terminal_state = TerminalState::save();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(interaction());
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => Ok(result?),
+        Err(_) => {
+            terminal_state.restore();
+            let _ = Term::stderr().show_cursor();
+            Err(Error::Timeout {
+                field: field_name.to_string(),
+                timeout,
+            })
+        }
+    }
+}
+
+/// The terminal settings of stdin from before an interaction, which is
+/// left in raw mode if the interaction is abandoned while reading a key.
+struct TerminalState {
+    #[cfg(unix)]
+    termios: Option<libc::termios>,
+}
+
+impl TerminalState {
+    fn save() -> Self {
+        #[cfg(unix)]
+        {
+            let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+            // SAFETY: `tcgetattr` initializes the termios when it succeeds.
+            let termios = unsafe {
+                (libc::tcgetattr(libc::STDIN_FILENO, termios.as_mut_ptr()) == 0)
+                    .then(|| termios.assume_init())
+            };
+            Self { termios }
+        }
+        #[cfg(not(unix))]
+        Self {}
+    }
+
+    fn restore(&self) {
+        #[cfg(unix)]
+        if let Some(termios) = self.termios.as_ref() {
+            // SAFETY: the termios was read from the same file descriptor.
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, termios) };
+        }
+    }
+}
+
+/// Reads text from the user until it passes validation.
+///
+/// Blank input, if the value can be skipped, [NULL_INPUT], if the value
+/// can be `null`, and [SKIP_REMAINING_INPUT] are returned without being
+/// validated. Rejected input is offered again
+/// as the initial text so it can be corrected rather than retyped.
+fn read_text(
+    field_name: impl Display,
+    settings: &ValueSettings,
+    mut validator: impl FnMut(&String) -> Result<(), String>,
+) -> Result<String, Error> {
+    let field_name = field_name.to_string();
+    let blank_default = blank_input_default(settings);
+    let (mut initial_text, prompt) = match blank_default.as_ref() {
+        Some(default) => {
+            let hint = Style::new()
+                .dim()
+                .for_stderr()
+                .apply_to(format!("[{default}]"));
+            (String::new(), format!("{field_name} {hint}"))
+        }
+        None => (default_text(settings), field_name.clone()),
+    };
+    let mut error_line = ErrorLine::default();
+    let mut failed_attempts = 0;
+    loop {
+        let prompt = prompt.clone();
+        let allow_empty = settings.can_skip || blank_default.is_some();
+        let input = interact(&field_name, settings.timeout, move || {
+            Input::<String>::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .with_initial_text(initial_text)
+                .allow_empty(allow_empty)
+                .interact_text()
+        })?;
+        let input = fill_blank(input, blank_default.as_ref());
+
+        let result = if input == OMIT_INPUT {
+            match read_omit(&field_name, settings)? {
+                Ok(blank) => return Ok(blank),
+                Err(err) => Err(err),
+            }
+        } else if (settings.can_skip && input.is_empty())
+            || (settings.allow_null && input == NULL_INPUT)
+            || input == SKIP_REMAINING_INPUT
+        {
+            Ok(())
+        } else {
+            validator(&input)
+        };
+        let err = match result {
+            Ok(()) => {
+                error_line.clear(&field_name, &input)?;
+                return Ok(input);
+            }
+            Err(err) => err,
+        };
+
+        failed_attempts += 1;
+        // The input may be sensitive so only the attempt is recorded.
+        #[cfg(feature = "tracing")]
+        tracing::debug!(field = %field_name, attempt = failed_attempts, "value failed validation");
+        check_attempts(&field_name, failed_attempts, settings.max_attempts)?;
+
+        error_line.show(&err)?;
+        initial_text = input;
+    }
+}
+
+/// What entering [OMIT_INPUT] reads as: blank input for a value which can
+/// be skipped, [Error::FieldOmitted] if omitting is allowed and otherwise
+/// an error explaining the input is invalid.
+fn read_omit(field_name: &str, settings: &ValueSettings) -> Result<Result<String, String>, Error> {
+    if settings.can_skip {
+        Ok(Ok(String::new()))
+    } else if settings.allow_omit {
+        Err(Error::FieldOmitted {
+            field: field_name.to_string(),
+        })
+    } else {
+        Ok(Err(format!(
+            "This field is required, {OMIT_INPUT} can only be used in a draft."
+        )))
+    }
+}
+
+/// The default as it is typed.
+fn default_text(settings: &ValueSettings) -> String {
+    match settings.default.as_ref() {
+        None => String::new(),
+        Some(serde_json::Value::String(default)) => default.clone(),
+        Some(default) => default.to_string(),
+    }
+}
+
+/// The text which blank input is read as, if the default is accepted by
+/// leaving the input blank rather than filled in as the initial text.
+fn blank_input_default(settings: &ValueSettings) -> Option<String> {
+    let accepts_default =
+        settings.empty_accepts_default && !settings.can_skip && settings.default.is_some();
+    accepts_default.then(|| default_text(settings))
+}
+
+/// Replaces blank input with the default, if there is one.
+fn fill_blank(input: String, default: Option<&String>) -> String {
+    match default {
+        Some(default) if input.is_empty() => default.clone(),
+        _ => input,
+    }
+}
+
+#[derive(Default)]
+/// A validation error shown beneath a prompt, rendered like dialoguer's
+/// own errors and cleared once the prompt is answered.
+pub(crate) struct ErrorLine {
+    shown: bool,
+}
+
+impl ErrorLine {
+    /// Replaces the echo of the rejected answer, and any previous error,
+    /// with the error.
+    pub(crate) fn show(&mut self, error: &str) -> io::Result<()> {
+        let term = Term::stderr();
+        term.clear_last_lines(if self.shown { 2 } else { 1 })?;
+
+        let mut message = String::new();
+        let _ = ColorfulTheme::default().format_error(&mut message, error);
+        term.write_line(&message)?;
+        self.shown = true;
+        Ok(())
+    }
+
+    /// Removes the error once the prompt is answered, writing the echo
+    /// of the answer in its place.
+    pub(crate) fn clear(&mut self, prompt: &str, answer: &str) -> io::Result<()> {
+        if !self.shown {
+            return Ok(());
+        }
+
+        let term = Term::stderr();
+        term.clear_last_lines(2)?;
+
+        let mut echo = String::new();
+        let _ = ColorfulTheme::default().format_input_prompt_selection(&mut echo, prompt, answer);
+        term.write_line(&echo)?;
+        self.shown = false;
+        Ok(())
+    }
+
+    /// Leaves the error in place, i.e. once another prompt follows it.
+    pub(crate) fn keep(&mut self) {
+        self.shown = false;
+    }
+}
+
+/// Reads every item of an array from a single line until each item
+/// is valid and the items pass the check.
+///
+/// `None` is returned if the array is skipped.
+pub(crate) fn read_inline<T, V>(
+    field_name: impl Display,
+    settings: &ValueSettings,
+    constraints: &CollectionConstraints,
+    mut validator: Option<V>,
+    check: impl Fn(&[T]) -> Result<(), String>,
+    skip_remaining: &mut bool,
+) -> Result<Option<Vec<T>>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    V: Validator<T>,
+    V::Err: Display,
+{
+    let mut parse = |input: &str| -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+        for (index, item) in constraints.split(input).into_iter().enumerate() {
+            let value = item
+                .parse::<T>()
+                .map_err(|e| format!("Item {} ({item}) is not valid: {e}", index + 1))?;
+            if let Some(validator) = validator.as_mut() {
+                validator
+                    .validate(&value)
+                    .map_err(|e| format!("Item {}: {e}", index + 1))?;
+            }
+            items.push(value);
+        }
+        Ok(items)
+    };
+
+    let settings = &ValueSettings {
+        default: settings
+            .default
+            .as_ref()
+            .map(|default| inline_default(default, constraints.delimiter)),
+        ..settings.clone()
+    };
+    let input = read_text(field_name, settings, |input| check(&parse(input)?))?;
+    if input == SKIP_REMAINING_INPUT {
+        *skip_remaining = true;
+        Ok(None)
+    } else if (settings.can_skip && input.is_empty())
+        || (settings.allow_null && input == NULL_INPUT)
+    {
+        Ok(None)
+    } else {
+        Ok(Some(parse(&input).unwrap_or_default()))
+    }
+}
+
+/// An array default as it is typed inline, its items joined by the delimiter.
+fn inline_default(default: &serde_json::Value, delimiter: char) -> serde_json::Value {
+    let items = match default {
+        serde_json::Value::Array(items) => items,
+        default => return default.clone(),
+    };
+    let separator = if delimiter.is_whitespace() {
+        delimiter.to_string()
+    } else {
+        format!("{delimiter} ")
+    };
+    let text = items
+        .iter()
+        .map(|item| match item {
+            serde_json::Value::String(s) => s.clone(),
+            item => item.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(&separator);
+    serde_json::Value::from(text)
+}
+
+/// Reads every item of an array from a text editor, one item per line,
+/// reopening the editor with the errors until the items are valid.
+///
+/// Closing the editor without changes accepts the items shown. `None` is
+/// returned if the array can be skipped and no items are saved.
+pub(crate) fn read_editor<T, V>(
+    field_name: impl Display,
+    settings: &ValueSettings,
+    mut validator: Option<V>,
+    check: impl Fn(&[T]) -> Result<(), String>,
+) -> Result<Option<Vec<T>>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    V: Validator<T>,
+    V::Err: Display,
+{
+    let header = format!("# Enter one {field_name} item per line, this line is ignored.");
+    let defaults = match settings.default.as_ref() {
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .map(|value| match value {
+                serde_json::Value::String(s) => s.clone(),
+                value => value.to_string(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut lines = defaults;
+    let mut errors = Vec::new();
+    let mut failed_attempts = 0;
+    loop {
+        let comments = [header.clone()]
+            .into_iter()
+            .chain(errors.iter().map(|e| format!("# Error: {e}")))
+            .collect::<Vec<_>>();
+        let buffer = comments
+            .iter()
+            .chain(lines.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let saved = interact(&field_name.to_string(), settings.timeout, move || {
+            Editor::new().require_save(false).edit(&buffer)
+        })?;
+
+        lines = match saved {
+            Some(saved) => editor_lines(&saved, &comments),
+            None if settings.can_skip => return Ok(None),
+            None => {
+                errors = vec!["This field is required and cannot be skipped.".to_string()];
+                failed_attempts += 1;
+                check_attempts(&field_name, failed_attempts, settings.max_attempts)?;
+                continue;
+            }
+        };
+        if lines.is_empty() && settings.can_skip {
+            return Ok(None);
+        }
+
+        errors.clear();
+        let mut items = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            // Whitespace is kept for strings, other items may be padded.
+            let value = match line
+                .parse::<T>()
+                .or_else(|e| line.trim().parse().map_err(|_| e))
+            {
+                Ok(value) => value,
+                Err(e) => {
+                    errors.push(format!("Item {} ({line}) is not valid: {e}", index + 1));
+                    continue;
+                }
+            };
+            if let Some(Err(e)) = validator.as_mut().map(|v| v.validate(&value)) {
+                errors.push(format!("Item {} ({line}): {e}", index + 1));
+                continue;
+            }
+            items.push(value);
+        }
+        if errors.is_empty() {
+            match check(&items) {
+                Ok(()) => return Ok(Some(items)),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        failed_attempts += 1;
+        check_attempts(&field_name, failed_attempts, settings.max_attempts)?;
     }
 }
 
+/// The items saved in the editor, without blank lines and the comment
+/// lines written above them.
+fn editor_lines(saved: &str, comments: &[String]) -> Vec<String> {
+    saved
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !comments.iter().any(|c| c == line))
+        .map(str::to_string)
+        .collect()
+}
+
 pub trait TraitIntBounds: PartialOrd + Debug + Display {
     fn max() -> Self;
     fn min() -> Self;
@@ -90,30 +555,106 @@ impl TraitIntBounds for usize {
     }
 }
 
-fn maybe_parse_value<V>(can_skip: bool, input: String) -> Option<V>
+fn check_attempts(
+    field_name: impl Display,
+    failed_attempts: usize,
+    max_attempts: Option<usize>,
+) -> Result<(), Error> {
+    if max_attempts.is_some_and(|max| failed_attempts >= max) {
+        return Err(Error::TooManyAttempts {
+            field: field_name.to_string(),
+            attempts: failed_attempts,
+        });
+    }
+
+    Ok(())
+}
+
+fn maybe_parse_value<V>(
+    settings: &ValueSettings,
+    skip_remaining: &mut bool,
+    input: String,
+) -> Option<V>
 where
     V: FromStr,
     V::Err: Debug,
 {
-    if can_skip && input.is_empty() {
+    if input == SKIP_REMAINING_INPUT {
+        *skip_remaining = true;
+        None
+    } else if (settings.can_skip && input.is_empty())
+        || (settings.allow_null && input == NULL_INPUT)
+    {
         None
     } else {
         Some(input.parse::<V>().unwrap())
     }
 }
 
-macro_rules! parse_primitives {
-    ($tp:ty, $msg:expr) => {
-        impl TraitIntBounds for $tp {
-            fn max() -> Self {
-                <$tp>::MAX
+/// Describes why the input could not be parsed into the type.
+trait ParseErrorMessage: FromStr {
+    fn parse_error_message(input: &str, _error: &Self::Err, msg: &str) -> String {
+        format!("Value ({input}) {msg}.")
+    }
+
+    /// Tidies the input before it is parsed.
+    fn normalize_input(input: &str) -> String {
+        input.to_string()
+    }
+}
+
+/// Parses the tidied input into the type, describing why it cannot be.
+fn parse_input<T: ParseErrorMessage>(input: &str, msg: &str) -> Result<T, String> {
+    let input = T::normalize_input(input);
+    input
+        .parse::<T>()
+        .map_err(|e| T::parse_error_message(&input, &e, msg))
+}
+
+/// Removes the whitespace between a leading sign and the number, so
+/// `- 5` is read as `-5`.
+fn join_sign(input: &str) -> String {
+    match input.strip_prefix(['-', '+']) {
+        Some(number) => format!("{}{}", &input[..1], number.trim_start()),
+        None => input.to_string(),
+    }
+}
+
+macro_rules! int_parse_error_message {
+    ($tp:ty, $name:expr) => {
+        impl ParseErrorMessage for $tp {
+            fn parse_error_message(input: &str, error: &ParseIntError, msg: &str) -> String {
+                let negative = input.strip_prefix('-').is_some_and(|digits| {
+                    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+                });
+                match error.kind() {
+                    IntErrorKind::InvalidDigit if negative && <$tp>::MIN == 0 => format!(
+                        "Value ({input}) is negative, which is not allowed for a {}.",
+                        $name
+                    ),
+                    IntErrorKind::PosOverflow => format!(
+                        "Value ({input}) is too large for a {} (max {}).",
+                        $name,
+                        <$tp>::MAX
+                    ),
+                    IntErrorKind::NegOverflow => format!(
+                        "Value ({input}) is too small for a {} (min {}).",
+                        $name,
+                        <$tp>::MIN
+                    ),
+                    _ => format!("Value ({input}) {msg}."),
+                }
             }
 
-            fn min() -> Self {
-                <$tp>::MIN
+            fn normalize_input(input: &str) -> String {
+                join_sign(input)
             }
         }
+    };
+}
 
+macro_rules! prompt_from_str {
+    ($tp:ty, $msg:expr) => {
         impl<'a, V> PromptValue<'a, V> for $tp
         where
             V: Validator<Self> + 'a,
@@ -122,30 +663,40 @@ macro_rules! parse_primitives {
             fn prompt(
                 field_name: impl Display,
                 mut validator: Option<V>,
-                can_skip: bool,
-            ) -> io::Result<Option<Self>> {
-                Input::with_theme(&ColorfulTheme::default())
-                    .with_prompt(field_name.to_string())
-                    .allow_empty(can_skip)
-                    .validate_with(|input: &String| -> Result<(), String> {
-                        if can_skip && input.is_empty() {
-                            return Ok(());
-                        }
-
-                        let value = input
-                            .parse::<Self>()
-                            .map_err(|_| format!("Value ({input}) {}.", $msg))?;
-
-                        if let Some(validator) = validator.as_mut() {
-                            validator.validate(&value).map_err(|e| e.to_string())
-                        } else {
-                            Ok(())
-                        }
-                    })
-                    .interact_text()
-                    .map(|input| maybe_parse_value(can_skip, input))
+                settings: ValueSettings,
+                skip_remaining: &mut bool,
+            ) -> Result<Option<Self>, Error> {
+                let input = read_text(field_name, &settings, |input| {
+                    check_decimal_places(input.trim(), settings.max_decimal_places)?;
+                    let value = parse_input::<Self>(input, $msg)?;
+
+                    if let Some(validator) = validator.as_mut() {
+                        validator.validate(&value).map_err(|e| e.to_string())
+                    } else {
+                        Ok(())
+                    }
+                })?;
+
+                let input = Self::normalize_input(&input);
+                Ok(maybe_parse_value(&settings, skip_remaining, input))
+            }
+        }
+    };
+}
+
+macro_rules! parse_primitives {
+    ($tp:ty, $msg:expr) => {
+        impl TraitIntBounds for $tp {
+            fn max() -> Self {
+                <$tp>::MAX
+            }
+
+            fn min() -> Self {
+                <$tp>::MIN
             }
         }
+
+        prompt_from_str!($tp, $msg);
     };
 }
 
@@ -159,3 +710,349 @@ parse_primitives!(i16, "is not a valid 16-bit number.");
 parse_primitives!(i8, "is not a valid 8-bit number.");
 parse_primitives!(f64, "is not a valid float.");
 parse_primitives!(f32, "is not a valid 32-bit float.");
+int_parse_error_message!(u64, "64-bit unsigned integer");
+int_parse_error_message!(u32, "32-bit unsigned integer");
+int_parse_error_message!(u16, "16-bit unsigned integer");
+int_parse_error_message!(u8, "8-bit unsigned integer");
+int_parse_error_message!(i64, "64-bit signed integer");
+int_parse_error_message!(i32, "32-bit signed integer");
+int_parse_error_message!(i16, "16-bit signed integer");
+int_parse_error_message!(i8, "8-bit signed integer");
+impl ParseErrorMessage for f64 {
+    fn normalize_input(input: &str) -> String {
+        join_sign(input)
+    }
+}
+impl ParseErrorMessage for f32 {
+    fn normalize_input(input: &str) -> String {
+        join_sign(input)
+    }
+}
+impl ParseErrorMessage for ByteSize {}
+prompt_from_str!(
+    ByteSize,
+    "is not a valid size, expected a number with an optional unit such as MB or GiB"
+);
+
+/// The units accepted when parsing a [ByteSize].
+static BYTE_UNITS: &[(&str, u64)] = &[
+    ("B", 1),
+    ("KB", 1_000),
+    ("MB", 1_000_000),
+    ("GB", 1_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("KiB", 1 << 10),
+    ("MiB", 1 << 20),
+    ("GiB", 1 << 30),
+    ("TiB", 1 << 40),
+    ("PiB", 1 << 50),
+];
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(try_from = "RawByteSize")]
+/// A number of bytes, parsed from a human friendly size like `10MB` or `1.5GiB`.
+pub struct ByteSize(pub u64);
+
+impl FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split_at = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split_at);
+        let unit = unit.trim();
+
+        let invalid_number = || format!("{s:?} does not start with a valid number");
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let fraction = fraction.trim_end_matches('0');
+        if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return Err(invalid_number());
+        }
+
+        let multiplier = if unit.is_empty() {
+            1
+        } else {
+            match BYTE_UNITS.iter().find(|(name, _)| *name == unit) {
+                Some((_, multiplier)) => *multiplier,
+                // Bits and other spellings are not guessed at, i.e. `Mb`.
+                None => {
+                    return Err(
+                        match BYTE_UNITS
+                            .iter()
+                            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+                        {
+                            Some((name, _)) => {
+                                format!("{unit:?} is ambiguous, did you mean {name}?")
+                            }
+                            None => format!("{unit:?} is not a known size unit"),
+                        },
+                    )
+                }
+            }
+        };
+
+        // Decimals are scaled with integers so no bytes are lost to rounding.
+        let too_large = || format!("{s:?} is too large");
+        let whole = match whole {
+            "" => 0,
+            whole => whole.parse::<u128>().map_err(|_| too_large())?,
+        };
+        let fraction_bytes = match fraction {
+            "" => 0,
+            fraction if fraction.len() > 20 => {
+                return Err(format!("{s:?} has too many decimal places"))
+            }
+            fraction => {
+                let scale = 10u128.pow(fraction.len() as u32);
+                let scaled =
+                    fraction.parse::<u128>().map_err(|_| invalid_number())? * multiplier as u128;
+                if !scaled.is_multiple_of(scale) {
+                    return Err(format!("{s:?} is not a whole number of bytes"));
+                }
+                scaled / scale
+            }
+        };
+
+        whole
+            .checked_mul(multiplier as u128)
+            .and_then(|bytes| bytes.checked_add(fraction_bytes))
+            .and_then(|bytes| u64::try_from(bytes).ok())
+            .map(Self)
+            .ok_or_else(too_large)
+    }
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = BYTE_UNITS
+            .iter()
+            .filter(|(_, multiplier)| self.0 != 0 && self.0.is_multiple_of(*multiplier))
+            .max_by_key(|(_, multiplier)| *multiplier);
+
+        match unit {
+            Some((name, multiplier)) => write!(f, "{}{name}", self.0 / multiplier),
+            None => write!(f, "{}B", self.0),
+        }
+    }
+}
+
+impl From<ByteSize> for serde_json::Value {
+    fn from(size: ByteSize) -> Self {
+        serde_json::Value::from(size.0)
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawByteSize {
+    Bytes(u64),
+    Text(String),
+}
+
+impl TryFrom<RawByteSize> for ByteSize {
+    type Error = String;
+
+    fn try_from(raw: RawByteSize) -> Result<Self, Self::Error> {
+        match raw {
+            RawByteSize::Bytes(bytes) => Ok(Self(bytes)),
+            RawByteSize::Text(text) => text.parse(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sign() {
+        assert_eq!(
+            parse_input::<i8>("-5", "is not a valid 8-bit number."),
+            Ok(-5)
+        );
+        assert_eq!(
+            parse_input::<i8>("- 5", "is not a valid 8-bit number."),
+            Ok(-5)
+        );
+        assert_eq!(
+            parse_input::<f64>("+ 1.5", "is not a valid float."),
+            Ok(1.5)
+        );
+        assert_eq!(
+            parse_input::<u8>("-5", "is not a valid positive 8-bit number."),
+            Err(
+                "Value (-5) is negative, which is not allowed for a 8-bit unsigned integer."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            parse_input::<u8>("- 5", "is not a valid positive 8-bit number."),
+            Err(
+                "Value (-5) is negative, which is not allowed for a 8-bit unsigned integer."
+                    .to_string()
+            )
+        );
+        let error = parse_input::<u8>("-x", "is not a valid positive 8-bit number.").unwrap_err();
+        assert!(error.starts_with("Value (-x) is not a valid positive 8-bit number"));
+    }
+
+    #[test]
+    fn test_empty_input_accepts_default() {
+        let settings = ValueSettings {
+            default: Some(serde_json::json!("localhost")),
+            empty_accepts_default: true,
+            ..Default::default()
+        };
+        let default = blank_input_default(&settings);
+        assert_eq!(fill_blank(String::new(), default.as_ref()), "localhost");
+        assert_eq!(
+            fill_blank("example.com".to_string(), default.as_ref()),
+            "example.com"
+        );
+
+        let settings = ValueSettings {
+            default: Some(serde_json::json!(8080)),
+            empty_accepts_default: true,
+            ..Default::default()
+        };
+        let default = blank_input_default(&settings);
+        let input = fill_blank(String::new(), default.as_ref());
+        assert_eq!(
+            parse_input::<u16>(&input, "is not a valid positive 16-bit number."),
+            Ok(8080)
+        );
+
+        // Blank input skips a field which can be skipped.
+        let settings = ValueSettings {
+            can_skip: true,
+            ..settings
+        };
+        assert_eq!(blank_input_default(&settings), None);
+
+        // Without the option the default is filled in as the initial text.
+        let settings = ValueSettings {
+            default: Some(serde_json::json!(8080)),
+            ..Default::default()
+        };
+        assert_eq!(blank_input_default(&settings), None);
+        assert_eq!(default_text(&settings), "8080");
+    }
+
+    #[test]
+    fn test_editor_lines() {
+        let comments = vec![
+            "# Enter one Channel item per line, this line is ignored.".to_string(),
+            "# Error: Item 1 (x) is too short".to_string(),
+        ];
+        let saved = "# Enter one Channel item per line, this line is ignored.\n\
+                     # Error: Item 1 (x) is too short\n\
+                     #general\n\
+                     \n  indented \n\
+                     # Error: typed by the user\n";
+        assert_eq!(
+            editor_lines(saved, &comments),
+            vec!["#general", "  indented ", "# Error: typed by the user"]
+        );
+    }
+
+    #[test]
+    fn test_inline_array_default() {
+        let constraints = CollectionConstraints::default();
+        let settings = ValueSettings {
+            default: Some(inline_default(
+                &serde_json::json!(["a.example.com", "b.example.com"]),
+                constraints.delimiter,
+            )),
+            ..Default::default()
+        };
+        let text = default_text(&settings);
+        assert_eq!(text, "a.example.com, b.example.com");
+        assert_eq!(
+            constraints.split(&text),
+            vec!["a.example.com", "b.example.com"]
+        );
+
+        let constraints = CollectionConstraints {
+            delimiter: ' ',
+            ..Default::default()
+        };
+        let default = inline_default(&serde_json::json!([80, 443]), constraints.delimiter);
+        let text = default.as_str().unwrap();
+        let ports = constraints
+            .split(text)
+            .into_iter()
+            .map(|item| parse_input::<u16>(item, "is not a valid port."))
+            .collect::<Result<Vec<_>, _>>();
+        assert_eq!(ports, Ok(vec![80, 443]));
+    }
+
+    #[test]
+    fn test_read_omit() {
+        let required = ValueSettings::default();
+        assert_eq!(
+            read_omit("Name", &required).unwrap(),
+            Err("This field is required, :omit can only be used in a draft.".to_string())
+        );
+
+        let draft = ValueSettings {
+            allow_omit: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            read_omit("Name", &draft),
+            Err(Error::FieldOmitted { field }) if field == "Name"
+        ));
+
+        let optional = ValueSettings {
+            can_skip: true,
+            allow_omit: true,
+            ..Default::default()
+        };
+        assert_eq!(read_omit("Name", &optional).unwrap(), Ok(String::new()));
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        let parse = |s: &str| s.parse::<ByteSize>();
+        assert_eq!(parse("10MB"), Ok(ByteSize(10_000_000)));
+        assert_eq!(parse("1.5GiB"), Ok(ByteSize(3 << 29)));
+        assert_eq!(parse("512"), Ok(ByteSize(512)));
+        assert_eq!(parse("1.10 KB"), Ok(ByteSize(1_100)));
+        assert_eq!(parse(".5KiB"), Ok(ByteSize(512)));
+        assert_eq!(parse("0.00000095367431640625MiB"), Ok(ByteSize(1)));
+
+        assert_eq!(
+            parse("10Mb"),
+            Err("\"Mb\" is ambiguous, did you mean MB?".to_string())
+        );
+        assert_eq!(
+            parse("10mb"),
+            Err("\"mb\" is ambiguous, did you mean MB?".to_string())
+        );
+        assert_eq!(
+            parse("1Kb"),
+            Err("\"Kb\" is ambiguous, did you mean KB?".to_string())
+        );
+        assert_eq!(
+            parse("1XB"),
+            Err("\"XB\" is not a known size unit".to_string())
+        );
+        assert_eq!(
+            parse("0.4B"),
+            Err("\"0.4B\" is not a whole number of bytes".to_string())
+        );
+        assert_eq!(
+            parse("1.5B"),
+            Err("\"1.5B\" is not a whole number of bytes".to_string())
+        );
+        assert!(parse("1.2.3KB").is_err());
+        assert!(parse("MB").is_err());
+        assert_eq!(
+            parse("20000PB"),
+            Err("\"20000PB\" is too large".to_string())
+        );
+    }
+}